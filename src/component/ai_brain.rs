@@ -6,11 +6,51 @@ pub enum MovementBehavior {
     Idle,
     BrownianWalk,
     Pursue,
+    Flee,
+    /// holds still while someone it's friendly with is around
+    Linger,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+/// standings at or below this are hostile
+pub const HOSTILE_STANDING: i32 = -50;
+/// standings at or above this are friendly
+pub const FRIENDLY_STANDING: i32 = 50;
+
+/// how an NPC feels about whoever it's paying attention to
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Attitude {
+    /// ignores them
     Passive,
+    /// attacks them
+    Hostile,
+    /// runs from them
+    Fearful,
+    /// waits around to be talked to. There's no trading yet, so that's all
+    /// friendliness gets anyone
+    Friendly,
+}
+
+impl Attitude {
+    /// the attitude for a standing toward someone. Hostile NPCs that are
+    /// outmatched (by <body>, compared to the other's) are fearful instead
+    pub fn from_standing(standing: i32, body: u8, other_body: u8) -> Attitude {
+        if standing <= HOSTILE_STANDING {
+            if body < other_body {
+                Attitude::Fearful
+            } else {
+                Attitude::Hostile
+            }
+        } else if standing >= FRIENDLY_STANDING {
+            Attitude::Friendly
+        } else {
+            Attitude::Passive
+        }
+    }
+
+    /// whether the attitude calls for doing something about the other
+    pub fn is_urgent(self) -> bool {
+        self == Attitude::Hostile || self == Attitude::Fearful
+    }
 }
 
 /// the "brain" of an NPC, aimed at being a basic finite state machine
@@ -32,3 +72,19 @@ impl Default for AIBrain {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attitude_from_standing() {
+        assert_eq!(Attitude::from_standing(-80, 3, 0), Attitude::Hostile);
+        assert_eq!(Attitude::from_standing(-80, 2, 3), Attitude::Fearful);
+        assert_eq!(Attitude::from_standing(0, 2, 3), Attitude::Passive);
+        assert_eq!(
+            Attitude::from_standing(FRIENDLY_STANDING, 0, 0),
+            Attitude::Friendly
+        );
+    }
+}
@@ -180,6 +180,19 @@ impl Character {
         false
     }
 
+    /// gets back <amt> stamina, up to the maximum
+    pub fn recover_stamina(&mut self, amt: u8) {
+        self.cur_stamina = self
+            .cur_stamina
+            .saturating_add(amt)
+            .min(self.compute_stamina());
+    }
+
+    /// knocks <amt> off current stamina, stopping at zero
+    pub fn wound(&mut self, amt: u8) {
+        self.cur_stamina = self.cur_stamina.saturating_sub(amt);
+    }
+
     pub fn spend_focus(&mut self, amt: u8) -> bool {
         if amt >= self.cur_focus {
             self.cur_focus -= amt;
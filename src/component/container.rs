@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+use specs::{Component, VecStorage};
+
+/// an entity holding items, by name, that can be opened and emptied
+#[derive(Clone, Component, Debug, Default, Deserialize, Serialize)]
+#[storage(VecStorage)]
+pub struct Container {
+    pub items: Vec<String>,
+}
@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+use specs::{Component, VecStorage};
+
+/// lets the player talk to an entity, by dialogue template file stem
+/// (dialogues/survivor.ron = "survivor" here)
+#[derive(Clone, Component, Debug, Default, Deserialize, Serialize)]
+#[storage(VecStorage)]
+pub struct Dialogue {
+    pub name: String,
+}
@@ -0,0 +1,75 @@
+use super::Character;
+use serde::{Deserialize, Serialize};
+use specs::{Component, VecStorage};
+
+/// energy gained over the time it takes to do a standard action, at base speed
+pub const BASE_SPEED: i32 = 100;
+/// extra speed for each point of grace
+pub const GRACE_SPEED: i32 = 15;
+/// the most energy an actor can save up while it has nothing to do
+const MAX_ENERGY: i32 = 100;
+
+/// what actions cost, in energy. A standard action costs the base speed
+pub const MOVE_COST: i32 = 100;
+pub const WAIT_COST: i32 = 100;
+pub const INTERACT_COST: i32 = 100;
+pub const ATTACK_COST: i32 = 150;
+pub const SEARCH_COST: i32 = 200;
+
+/// how ready an actor is to act. Actors gain energy as time passes, at their
+/// speed, and spend it on actions. They can act whenever they aren't in debt
+#[derive(Copy, Clone, Component, Debug, Default, Deserialize, Serialize)]
+#[storage(VecStorage)]
+pub struct Energy {
+    pub energy: i32,
+}
+
+impl Energy {
+    /// energy gained per standard action's worth of time
+    pub fn speed(character: Option<&Character>) -> i32 {
+        BASE_SPEED + character.map_or(0, |c| i32::from(c.grace()) * GRACE_SPEED)
+    }
+
+    pub fn ready(self) -> bool {
+        self.energy >= 0
+    }
+
+    pub fn spend(&mut self, cost: i32) {
+        self.energy -= cost;
+    }
+
+    /// gains <fraction> of <speed> worth of energy
+    pub fn gain(&mut self, speed: i32, fraction: f32) {
+        let gained = (speed as f32 * fraction).round() as i32;
+        self.energy = (self.energy + gained).min(MAX_ENERGY);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn faster_actors_act_more_often() {
+        let mut quick = Character::default();
+        quick.set_grace(4);
+        let speeds = [Energy::speed(None), Energy::speed(Some(&quick))];
+        let mut actions = [0, 0];
+        for (speed, count) in speeds.iter().zip(actions.iter_mut()) {
+            let mut energy = Energy::default();
+            for _ in 0..40 {
+                energy.gain(*speed, 0.25);
+                while energy.ready() {
+                    energy.spend(MOVE_COST);
+                    *count += 1;
+                }
+            }
+        }
+        assert!(actions[1] > actions[0]);
+        // heavy actions take longer
+        let mut energy = Energy::default();
+        energy.spend(ATTACK_COST);
+        energy.gain(BASE_SPEED, 1.0);
+        assert!(!energy.ready());
+    }
+}
@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+use specs::{Component, VecStorage};
+
+/// the faction an entity belongs to, by faction template file stem
+/// (factions/survivors.ron = "survivors" here)
+#[derive(Clone, Component, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[storage(VecStorage)]
+pub struct Faction {
+    pub name: String,
+}
+
+impl Faction {
+    pub fn new(name: &str) -> Faction {
+        Faction {
+            name: name.to_string(),
+        }
+    }
+}
@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+use specs::{Component, VecStorage};
+
+/// things an entity is carrying, by name
+#[derive(Clone, Component, Debug, Default, Deserialize, Serialize)]
+#[storage(VecStorage)]
+pub struct Inventory {
+    pub items: Vec<String>,
+}
+
+impl Inventory {
+    pub fn add(&mut self, item: &str) {
+        self.items.push(item.to_string());
+    }
+}
@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+use specs::{Component, VecStorage};
+
+/// an entity that can be picked up, going into the inventory as <name>
+#[derive(Clone, Component, Debug, Default, Deserialize, Serialize)]
+#[storage(VecStorage)]
+pub struct Item {
+    pub name: String,
+}
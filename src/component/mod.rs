@@ -4,10 +4,17 @@ use specs::World;
 pub mod ai_brain;
 mod character;
 mod colors;
+mod container;
 mod cursor;
 mod description;
+mod dialogue;
+mod energy;
+mod faction;
 mod icon_ref;
+mod inventory;
+mod item;
 mod move_plan;
+mod movement_mode;
 mod notification;
 mod opaque;
 mod orientation;
@@ -15,14 +22,23 @@ mod player;
 mod position;
 mod region;
 mod solid;
+mod template;
+mod verbs;
 
 pub use self::ai_brain::AIBrain;
 pub use self::character::Character;
 pub use self::colors::*;
+pub use self::container::Container;
 pub use self::cursor::Cursor;
 pub use self::description::Description;
+pub use self::dialogue::Dialogue;
+pub use self::energy::*;
+pub use self::faction::Faction;
 pub use self::icon_ref::IconRef;
+pub use self::inventory::Inventory;
+pub use self::item::Item;
 pub use self::move_plan::MovePlan;
+pub use self::movement_mode::MovementMode;
 pub use self::notification::NotificationInteraction;
 pub use self::opaque::Opaque;
 pub use self::orientation::*;
@@ -30,16 +46,25 @@ pub use self::player::Player;
 pub use self::position::Pos;
 pub use self::region::Region;
 pub use self::solid::Solid;
+pub use self::template::Template;
+pub use self::verbs::Verbs;
 
 /// initializes all components
 pub fn init(world: &mut World) {
     world.register::<AIBrain>();
     world.register::<Character>();
     world.register::<Colors>();
+    world.register::<Container>();
     world.register::<Cursor>();
     world.register::<Description>();
+    world.register::<Dialogue>();
+    world.register::<Energy>();
+    world.register::<Faction>();
     world.register::<IconRef>();
+    world.register::<Inventory>();
+    world.register::<Item>();
     world.register::<MovePlan>();
+    world.register::<MovementMode>();
     world.register::<NotificationInteraction>();
     world.register::<Player>();
     world.register::<Pos>();
@@ -47,4 +72,6 @@ pub fn init(world: &mut World) {
     world.register::<Orientation>();
     world.register::<Region>();
     world.register::<Solid>();
+    world.register::<Template>();
+    world.register::<Verbs>();
 }
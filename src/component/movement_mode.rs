@@ -0,0 +1,106 @@
+use super::MOVE_COST;
+use serde::{Deserialize, Serialize};
+use specs::{Component, VecStorage};
+
+/// how an actor gets around. Faster modes cover more ground per move but cost
+/// stamina, make noise and leave less attention for the surroundings
+#[derive(Copy, Clone, Component, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[storage(VecStorage)]
+pub enum MovementMode {
+    #[default]
+    Walk,
+    Sneak,
+    Run,
+    Sprint,
+}
+
+impl MovementMode {
+    pub fn name(self) -> &'static str {
+        match self {
+            MovementMode::Walk => "walking",
+            MovementMode::Sneak => "sneaking",
+            MovementMode::Run => "running",
+            MovementMode::Sprint => "sprinting",
+        }
+    }
+
+    /// how many single tile steps one move takes
+    pub fn steps(self) -> u8 {
+        match self {
+            MovementMode::Walk | MovementMode::Sneak => 1,
+            MovementMode::Run => 2,
+            MovementMode::Sprint => 3,
+        }
+    }
+
+    /// energy spent per tile stepped
+    pub fn step_cost(self) -> i32 {
+        match self {
+            MovementMode::Walk => MOVE_COST,
+            MovementMode::Sneak => MOVE_COST * 3 / 2,
+            MovementMode::Run => MOVE_COST / 2,
+            MovementMode::Sprint => MOVE_COST / 3 + 1,
+        }
+    }
+
+    /// stamina spent per move. Moving at an easy pace recovers stamina instead
+    pub fn stamina_cost(self) -> u8 {
+        match self {
+            MovementMode::Walk | MovementMode::Sneak => 0,
+            MovementMode::Run => 1,
+            MovementMode::Sprint => 2,
+        }
+    }
+
+    /// how far away a moving actor can be heard, in tiles
+    pub fn noise(self) -> i32 {
+        match self {
+            MovementMode::Sneak => 0,
+            MovementMode::Walk => 2,
+            MovementMode::Run => 6,
+            MovementMode::Sprint => 10,
+        }
+    }
+
+    /// how much of the usual sight range an actor pays attention to
+    pub fn perception(self) -> f32 {
+        match self {
+            MovementMode::Walk | MovementMode::Sneak => 1.0,
+            MovementMode::Run => 0.75,
+            MovementMode::Sprint => 0.5,
+        }
+    }
+
+    /// how easy an actor is to spot, as a share of the observer's sight range
+    pub fn visibility(self) -> f32 {
+        match self {
+            MovementMode::Sneak => 0.5,
+            _ => 1.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn faster_modes_cover_more_ground_per_action() {
+        let modes = [
+            MovementMode::Sneak,
+            MovementMode::Walk,
+            MovementMode::Run,
+            MovementMode::Sprint,
+        ];
+        for pair in modes.windows(2) {
+            let (slow, fast) = (pair[0], pair[1]);
+            assert!(fast.step_cost() < slow.step_cost());
+            assert!(fast.noise() > slow.noise());
+        }
+        // a run or sprint takes about as long as a walk, just covering more tiles
+        for mode in &modes[2..] {
+            let total = mode.step_cost() * i32::from(mode.steps());
+            assert!((MOVE_COST..MOVE_COST * 11 / 10).contains(&total));
+        }
+    }
+}
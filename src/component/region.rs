@@ -7,24 +7,34 @@ use specs::{Component, VecStorage};
 pub struct Region {
     pub x: i32,
     pub y: i32,
+    /// vertical level: 0 is the ground, positive levels are upper floors and
+    /// negative levels are basements
+    #[serde(default)]
+    pub z: i32,
 }
 
-use crate::constants::{MAP_HEIGHT, MAP_WIDTH, WORLD_SIZE};
+use crate::constants::{MAP_HEIGHT, MAP_WIDTH};
 impl Region {
     pub fn new(x: i32, y: i32) -> Region {
-        Region { x, y }
+        Region { x, y, z: 0 }
     }
-    // used in map generation
-    pub fn to_offset(self) -> [i32; 2] {
-        [self.x * MAP_WIDTH as i32, self.y * MAP_HEIGHT as i32]
+
+    /// the region at level <z> above (or below) this one
+    pub fn on_level(self, z: i32) -> Region {
+        Region { z, ..self }
     }
 
+    /// a readable name for the region's level
+    pub fn level_name(self) -> String {
+        match self.z {
+            0 => "ground floor".to_string(),
+            z if z > 0 => format!("floor {}", z + 1),
+            z => format!("basement {}", -z),
+        }
+    }
     // used in map generation
-    pub fn to_unsigned(self) -> [u64; 2] {
-        [
-            (self.x + (WORLD_SIZE as i32 / 2)) as u64,
-            (self.y + (WORLD_SIZE as i32 / 2)) as u64,
-        ]
+    pub fn to_offset(self) -> [i32; 2] {
+        [self.x * MAP_WIDTH as i32, self.y * MAP_HEIGHT as i32]
     }
 }
 
@@ -39,9 +49,6 @@ impl From<Region> for Coord<i32> {
 
 impl From<Coord<i32>> for Region {
     fn from(coord: Coord<i32>) -> Region {
-        Region {
-            x: coord.x,
-            y: coord.y,
-        }
+        Region::new(coord.x, coord.y)
     }
 }
@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+use specs::{Component, VecStorage};
+
+/// the entity template an entity was spawned from, by file stem
+/// (entities/shockroach.ron = "shockroach" here)
+#[derive(Clone, Component, Debug, Default, Deserialize, Serialize)]
+#[storage(VecStorage)]
+pub struct Template {
+    pub name: String,
+}
+
+impl Template {
+    pub fn new(name: &str) -> Template {
+        Template {
+            name: name.to_string(),
+        }
+    }
+}
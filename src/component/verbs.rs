@@ -0,0 +1,11 @@
+use crate::resource::InteractionMethod;
+use serde::{Deserialize, Serialize};
+use specs::{Component, VecStorage};
+
+/// the verbs available for an entity. Entities without this get verbs to match
+/// their other components
+#[derive(Clone, Component, Debug, Default, Deserialize, Serialize)]
+#[storage(VecStorage)]
+pub struct Verbs {
+    pub available: Vec<InteractionMethod>,
+}
@@ -27,5 +27,8 @@ pub const SPRITE_MAP: &str = "./tiles.png";
 pub const SPRITE_WIDTH: i32 = 16;
 pub const SPRITE_HEIGHT: i32 = 64;
 
-/// world is always square, this is the length of a side
+/// world is always square, this is the default length of a side
 pub const WORLD_SIZE: usize = 250;
+
+/// where saved games are written
+pub const SAVE_FILE: &str = "./save/game.ron";
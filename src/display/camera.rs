@@ -0,0 +1,88 @@
+use crate::component::{Pos, Region};
+use crate::constants::{MAP_HEIGHT, MAP_WIDTH};
+
+/// which part of the world the map area shows. Tiles are stitched together from
+/// whichever regions they fall in, so the view scrolls smoothly across borders
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Camera {
+    /// world tile coordinates of the top left of the map area
+    origin: [i32; 2],
+    /// the level being shown
+    z: i32,
+}
+
+impl Camera {
+    /// a camera centered on <pos> in <region>
+    pub fn centered_on(region: Region, pos: Pos) -> Camera {
+        let offset = region.to_offset();
+        Camera {
+            origin: [
+                offset[0] + pos.x as i32 - MAP_WIDTH as i32 / 2,
+                offset[1] + pos.y as i32 - MAP_HEIGHT as i32 / 2,
+            ],
+            z: region.z,
+        }
+    }
+
+    /// where <pos> in <region> is in the map area, if it's in view
+    pub fn to_screen(self, region: Region, pos: Pos) -> Option<(i32, i32)> {
+        if region.z != self.z {
+            return None;
+        }
+        let offset = region.to_offset();
+        let x = offset[0] + pos.x as i32 - self.origin[0];
+        let y = offset[1] + pos.y as i32 - self.origin[1];
+        if x < 0 || y < 0 || x >= MAP_WIDTH as i32 || y >= MAP_HEIGHT as i32 {
+            return None;
+        }
+        Some((x, y))
+    }
+
+    /// the region and position shown at <x>, <y> in the map area
+    pub fn to_world(self, x: i32, y: i32) -> (Region, Pos) {
+        let (wx, wy) = (self.origin[0] + x, self.origin[1] + y);
+        let (width, height) = (MAP_WIDTH as i32, MAP_HEIGHT as i32);
+        let region = Region {
+            x: wx.div_euclid(width),
+            y: wy.div_euclid(height),
+            z: self.z,
+        };
+        let pos = Pos::new(
+            wx.rem_euclid(width) as usize,
+            wy.rem_euclid(height) as usize,
+        );
+        (region, pos)
+    }
+
+    /// every cell of the map area, with the region and position it shows
+    pub fn cells(self) -> impl Iterator<Item = (i32, i32, Region, Pos)> {
+        (0..MAP_HEIGHT as i32).flat_map(move |y| {
+            (0..MAP_WIDTH as i32).map(move |x| {
+                let (region, pos) = self.to_world(x, y);
+                (x, y, region, pos)
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn camera_crosses_region_borders() {
+        let region = Region::new(3, -2);
+        let camera = Camera::centered_on(region, Pos::new(1, 1));
+        let center = (MAP_WIDTH as i32 / 2, MAP_HEIGHT as i32 / 2);
+        assert_eq!(camera.to_screen(region, Pos::new(1, 1)), Some(center));
+        // the tile up and to the left of the player is across both borders
+        let (other, pos) = camera.to_world(center.0 - 2, center.1 - 2);
+        assert_eq!(other, Region::new(2, -3));
+        assert_eq!(pos, Pos::new(MAP_WIDTH - 1, MAP_HEIGHT - 1));
+        assert_eq!(
+            camera.to_screen(other, pos),
+            Some((center.0 - 2, center.1 - 2))
+        );
+        assert_eq!(camera.to_screen(region.on_level(1), Pos::new(1, 1)), None);
+    }
+}
@@ -11,8 +11,11 @@ use tcod::{
     BackgroundFlag, Console, FontLayout, FontType, Map, RootConsole, TextAlignment,
 };
 
+mod camera;
 mod ui;
 
+use camera::Camera;
+
 type TColor = tcod::colors::Color;
 
 use super::constants::{
@@ -22,6 +25,10 @@ use super::constants::{
 pub struct Display {
     pub root: Root,
     pub map: Map,
+    /// where the map area was last looking, kept for drawing the ui over it
+    camera: Camera,
+    /// the world map's fog, worked out once per view
+    map_fog: ui::MapFog,
 }
 
 #[derive(SystemData)]
@@ -32,6 +39,7 @@ pub struct DisplayData<'a> {
     cursors: ReadStorage<'a, Cursor>,
     descriptions: ReadStorage<'a, Description>,
     icons: ReadStorage<'a, IconRef>,
+    modes: ReadStorage<'a, MovementMode>,
     orientations: ReadStorage<'a, Orientation>,
     players: ReadStorage<'a, Player>,
     positions: ReadStorage<'a, Pos>,
@@ -42,6 +50,7 @@ pub struct DisplayData<'a> {
     maps: Read<'a, RegionMaps>,
     assets: Read<'a, Assets>,
     collisions: Read<'a, CollisionMaps>,
+    explored: Write<'a, Explored>,
     ui_queue: Read<'a, UIQueue>,
     input: Write<'a, UserInput>,
 }
@@ -66,6 +75,8 @@ impl Display {
         Display {
             root,
             map: Map::new(MAP_WIDTH as i32, MAP_HEIGHT as i32),
+            camera: Camera::default(),
+            map_fog: ui::MapFog::default(),
         }
     }
 }
@@ -97,7 +108,7 @@ impl<'a> System<'a> for Display {
             }
             _ => match data.state.render_mode {
                 RenderMode::Normal => {
-                    self.render_map_normal(&data);
+                    self.render_map_normal(&mut data);
                     self.render_ui(&data);
                 }
                 RenderMode::Collision => {
@@ -146,21 +157,26 @@ impl Display {
         self.root.flush();
     }
 
-    fn render_map_normal<'a>(&mut self, data: &DisplayData) {
+    fn render_map_normal<'a>(&mut self, data: &mut DisplayData) {
         let mut player_pos: Pos = Pos::default();
         let mut player_region: Region = Region::default();
 
-        for (region, pos, character, _player) in (
+        let mut perception: f32 = 1.0;
+
+        for (region, pos, character, mode, _player) in (
             &data.regions,
             &data.positions,
             &data.characters,
+            data.modes.maybe(),
             &data.players,
         )
             .join()
         {
+            let mode = mode.cloned().unwrap_or_default();
             ui::draw_sidebar_frame(&self.root, &data.assets);
             ui::draw_stats(&self.root, &data.assets, character);
-            ui::draw_status_bar(&self.root, character, &data.state);
+            ui::draw_status_bar(&self.root, character, mode, &data.state);
+            perception = mode.perception();
             player_pos = *pos;
             player_region = *region;
             ui::draw_worldmap(
@@ -171,31 +187,63 @@ impl Display {
                 &data.state,
             );
         }
-        let map = data.maps.get(player_region);
-
-        // update fov map before computing fov
-        for (pos, tile) in map.iter() {
-            self.map
-                .set(pos.x as i32, pos.y as i32, tile.transparent, tile.walkable)
+        self.camera = Camera::centered_on(player_region, player_pos);
+        let camera = self.camera;
+        let (player_x, player_y) = camera
+            .to_screen(player_region, player_pos)
+            .unwrap_or_default();
+
+        // update fov map before computing fov, stitching in the neighboring
+        // regions. Anything not generated yet blocks sight
+        for (x, y, region, pos) in camera.cells() {
+            match data.maps.get_tile(region, pos) {
+                Some(tile) => self.map.set(x, y, tile.transparent, tile.walkable),
+                None => self.map.set(x, y, false, false),
+            }
         }
 
-        // Compute the FOV
+        // Compute the FOV, which narrows when the player is moving fast
         self.map.compute_fov(
-            player_pos.x as i32,
-            player_pos.y as i32,
-            SCREEN_WIDTH,
+            player_x,
+            player_y,
+            (SCREEN_WIDTH as f32 * perception) as i32,
             true,
             FovAlgorithm::Basic,
         );
+        data.explored.clear_visible();
+        for (x, y, region, pos) in camera.cells() {
+            if let Some(tile) = data.maps.get_tile(region, pos) {
+                if self.map.is_in_fov(x, y) {
+                    data.explored.see(data.world.wrap(region), pos, tile);
+                }
+            }
+        }
 
-        // draw all tiles
-        for (pos, tile) in map.iter() {
+        // draw tiles the player can see, or remembers seeing. The rest stay blank
+        for (x, y, region, pos) in camera.cells() {
+            let tile = data.maps.get_tile(region, pos);
+            // what the player remembers goes by where it is in the world
+            let known = data.world.wrap(region);
+            let look = match tile {
+                Some(tile) if data.explored.visible(known, pos) => RememberedTile::from(tile),
+                _ if data.explored.seen(known, pos) => {
+                    match data
+                        .explored
+                        .remembered(known, pos)
+                        .or_else(|| tile.map(RememberedTile::from))
+                    {
+                        Some(look) => look,
+                        None => continue,
+                    }
+                }
+                _ => continue,
+            };
             self.root.put_char_ex(
-                pos.x as i32,
-                pos.y as i32,
-                tile.icon,
-                TColor::from(tile.fg),
-                TColor::from(tile.bg),
+                x,
+                y,
+                look.icon,
+                TColor::from(look.fg),
+                TColor::from(look.bg),
             );
         }
 
@@ -209,16 +257,17 @@ impl Display {
         )
             .join()
         {
-            let ipos: Coord<i32> = (*pos).into();
-            if self.map.is_in_fov(ipos.x, ipos.y) && *region == player_region {
-                self.root.put_char(
-                    ipos.x,
-                    ipos.y,
-                    data.assets.get_icon(&icon.name).ch(),
-                    BackgroundFlag::None,
-                );
-                self.root
-                    .set_char_foreground(ipos.x, ipos.y, TColor::from(color.fg));
+            match camera.to_screen(*region, *pos) {
+                Some((x, y)) if self.map.is_in_fov(x, y) => {
+                    self.root.put_char(
+                        x,
+                        y,
+                        data.assets.get_icon(&icon.name).ch(),
+                        BackgroundFlag::None,
+                    );
+                    self.root.set_char_foreground(x, y, TColor::from(color.fg));
+                }
+                _ => {}
             }
         }
 
@@ -247,13 +296,17 @@ impl Display {
         let night = Color::new(12, 12, 12);
 
         // lighting pass
-        for (pos, _) in map.iter() {
-            let ipos: Coord<i32> = pos.into();
+        let player_screen = Pos::new(player_x as usize, player_y as usize);
+        for (x, y, region, pos) in camera.cells() {
+            if !data.explored.seen(data.world.wrap(region), pos) {
+                continue;
+            }
+            let ipos = Coord { x, y };
             let orig_fg = Color::from(self.root.get_char_foreground(ipos.x, ipos.y));
             let orig_bg = Color::from(self.root.get_char_background(ipos.x, ipos.y));
             let mut fg = orig_fg;
             let mut bg = orig_bg;
-            let dist = distance(player_pos, pos);
+            let dist = distance(player_screen, Pos::new(x as usize, y as usize));
             let light_radius = 20.0;
             // this figures out the intensity and radius of the player-emitted light area
             let rel_dist =
@@ -283,13 +336,15 @@ impl Display {
         }
 
         // draw in the cursor highlight
-        for (pos, ..) in (&data.positions, &data.cursors).join() {
-            self.root.set_char_background(
-                pos.x as i32,
-                pos.y as i32,
-                TColor::new(110, 180, 144),
-                BackgroundFlag::Overlay,
-            );
+        for (region, pos, ..) in (&data.regions, &data.positions, &data.cursors).join() {
+            if let Some((x, y)) = self.camera.to_screen(*region, *pos) {
+                self.root.set_char_background(
+                    x,
+                    y,
+                    TColor::new(110, 180, 144),
+                    BackgroundFlag::Overlay,
+                );
+            }
         }
     }
 
@@ -297,32 +352,42 @@ impl Display {
     /// render in collision mode, showing solids in a lighter background color
     /// (also ignores fov so it's a wallhack)
     fn render_map_collision<'a>(&mut self, data: &DisplayData) {
+        let mut player_pos: Pos = Pos::default();
         let mut player_region: Region = Region::default();
 
-        for (region, character, _player) in (&data.regions, &data.characters, &data.players).join()
+        for (region, pos, character, mode, _player) in (
+            &data.regions,
+            &data.positions,
+            &data.characters,
+            data.modes.maybe(),
+            &data.players,
+        )
+            .join()
         {
+            let mode = mode.cloned().unwrap_or_default();
             ui::draw_sidebar_frame(&self.root, &data.assets);
             ui::draw_stats(&self.root, &data.assets, character);
-            ui::draw_status_bar(&self.root, character, &data.state);
+            ui::draw_status_bar(&self.root, character, mode, &data.state);
+            player_pos = *pos;
             player_region = *region;
         }
-        let map = &data.maps.get(player_region);
+        self.camera = Camera::centered_on(player_region, player_pos);
+        let camera = self.camera;
 
         // draw all tiles
-        for (pos, tile) in map.iter() {
-            let collision = &data.collisions.get(player_region, pos);
+        for (x, y, region, pos) in camera.cells() {
+            let tile = match data.maps.get_tile(region, pos) {
+                Some(tile) => tile,
+                None => continue,
+            };
+            let collision = data.collisions.get(region, pos);
             let bg: Color = if !collision && tile.walkable {
                 Color::new(32, 32, 32)
             } else {
                 Color::new(180, 180, 180)
             };
-            self.root.put_char_ex(
-                pos.x as i32,
-                pos.y as i32,
-                tile.icon,
-                TColor::from(tile.fg),
-                TColor::from(bg),
-            );
+            self.root
+                .put_char_ex(x, y, tile.icon, TColor::from(tile.fg), TColor::from(bg));
         }
 
         // draw all npcs, also snag the one under the cursor if applicable
@@ -335,23 +400,22 @@ impl Display {
         )
             .join()
         {
-            if *region == player_region {
+            if let Some((x, y)) = camera.to_screen(*region, *pos) {
                 self.root.put_char(
-                    pos.x as i32,
-                    pos.y as i32,
+                    x,
+                    y,
                     data.assets.get_icon(&icon.name).ch(),
                     BackgroundFlag::None,
                 );
-                self.root
-                    .set_char_foreground(pos.x as i32, pos.y as i32, TColor::from(color.fg));
+                self.root.set_char_foreground(x, y, TColor::from(color.fg));
             }
         }
 
         for (region, pos, _solid, ..) in (&data.regions, &data.positions, &data.solids).join() {
-            if *region == player_region {
+            if let Some((x, y)) = camera.to_screen(*region, *pos) {
                 self.root.set_char_background(
-                    pos.x as i32,
-                    pos.y as i32,
+                    x,
+                    y,
                     TColor::from(Color::new(180, 180, 180)),
                     BackgroundFlag::Set,
                 );
@@ -361,20 +425,23 @@ impl Display {
         self.draw_player(&data);
 
         // draw in the cursor highlight
-        for (pos, ..) in (&data.positions, &data.cursors).join() {
-            self.root.set_char_background(
-                pos.x as i32,
-                pos.y as i32,
-                TColor::new(110, 180, 144),
-                BackgroundFlag::Overlay,
-            );
+        for (region, pos, ..) in (&data.regions, &data.positions, &data.cursors).join() {
+            if let Some((x, y)) = self.camera.to_screen(*region, *pos) {
+                self.root.set_char_background(
+                    x,
+                    y,
+                    TColor::new(110, 180, 144),
+                    BackgroundFlag::Overlay,
+                );
+            }
         }
     }
 
     fn draw_player(&mut self, data: &DisplayData) {
         // draw player, make sure it ends up on top
-        for (orientation, pos, icon, color, ..) in (
+        for (orientation, region, pos, icon, color, ..) in (
             &data.orientations,
+            &data.regions,
             &data.positions,
             &data.icons,
             &data.colors,
@@ -400,46 +467,54 @@ impl Display {
                     west = true;
                 }
             }
+            let (x, y) = match self.camera.to_screen(*region, *pos) {
+                Some(screen) => screen,
+                None => continue,
+            };
             self.root.put_char(
-                pos.x as i32,
-                pos.y as i32,
+                x,
+                y,
                 data.assets
                     .get_icon(&icon.name)
                     .connected(north, south, east, west)
                     .ch(),
                 BackgroundFlag::None,
             );
-            self.root
-                .set_char_foreground(pos.x as i32, pos.y as i32, TColor::from(color.fg))
+            self.root.set_char_foreground(x, y, TColor::from(color.fg))
         }
     }
 
     /// renders UI elements, done after map draw passes
     fn render_ui<'a>(&mut self, data: &DisplayData) {
-        let mut cursor_pos: Pos = Pos::default();
+        let mut cursor: Option<(Region, Pos)> = None;
         let mut player_region: Region = Region::default();
-        let mut has_cursor: bool = false;
 
-        for (region, character, _player) in (&data.regions, &data.characters, &data.players).join()
+        for (region, character, mode, _player) in (
+            &data.regions,
+            &data.characters,
+            data.modes.maybe(),
+            &data.players,
+        )
+            .join()
         {
+            let mode = mode.cloned().unwrap_or_default();
             ui::draw_sidebar_frame(&self.root, &data.assets);
             ui::draw_stats(&self.root, &data.assets, character);
-            ui::draw_status_bar(&self.root, character, &data.state);
+            ui::draw_status_bar(&self.root, character, mode, &data.state);
             player_region = *region;
         }
 
         // find the cursor position
-        for (pos, _cursor) in (&data.positions, &data.cursors).join() {
-            cursor_pos.x = pos.x;
-            cursor_pos.y = pos.y;
-            has_cursor = true;
+        for (region, pos, _cursor) in (&data.regions, &data.positions, &data.cursors).join() {
+            cursor = Some((*region, *pos));
         }
-
-        // get the current map
-        let map = &data.maps.get(player_region);
+        let in_view = |(region, pos)| match self.camera.to_screen(region, pos) {
+            Some((x, y)) => self.map.is_in_fov(x, y),
+            None => false,
+        };
 
         // find an entity under the cursor, if it exists
-        if has_cursor && self.map.is_in_fov(cursor_pos.x as i32, cursor_pos.y as i32) {
+        if let Some((cursor_region, cursor_pos)) = cursor.filter(|&c| in_view(c)) {
             let mut found_entity = false;
             for (region, pos, icon, color, desc) in (
                 &data.regions,
@@ -450,7 +525,7 @@ impl Display {
             )
                 .join()
             {
-                if *pos == cursor_pos && *region == player_region {
+                if *pos == cursor_pos && *region == cursor_region {
                     ui::draw_entity_info(
                         &self.root,
                         data.assets.get_icon(&icon.name).ch(),
@@ -461,12 +536,33 @@ impl Display {
                 }
             }
             if !found_entity {
-                ui::draw_tile_info(&self.root, map.get(cursor_pos).unwrap());
+                if let Some(tile) = data.maps.get_tile(cursor_region, cursor_pos) {
+                    ui::draw_tile_info(&self.root, tile);
+                }
             }
         }
 
         if let Some(widget) = data.ui_queue.get() {
-            ui::draw_centered_dialog(&self.root, widget);
+            let (view, footer) = {
+                let lock = widget.lock().unwrap();
+                (lock.map_view(), lock.get_footer())
+            };
+            match view {
+                Some(view) => ui::draw_world_map_view(
+                    &self.root,
+                    &data.assets,
+                    &data.world,
+                    &data.explored,
+                    &mut self.map_fog,
+                    &data.state,
+                    ui::WorldMapFrame {
+                        view,
+                        player: player_region,
+                        footer,
+                    },
+                ),
+                None => ui::draw_centered_dialog(&self.root, widget),
+            }
         }
     }
 }
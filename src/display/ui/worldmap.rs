@@ -1,11 +1,138 @@
 use super::util::*;
 use crate::component::Region;
-use crate::constants::SIDEBAR_WIDTH;
-use crate::resource::{Assets, GameState, MapMode, WorldState};
-use tcod::{Console, TextAlignment};
+use crate::constants::{MAP_HEIGHT, MAP_WIDTH, SIDEBAR_WIDTH};
+use crate::resource::{Assets, Explored, GameState, Hydrology, MapMode, MapView, WorldState};
+use tcod::{BackgroundFlag, Console, TextAlignment};
 
 type TColor = tcod::colors::Color;
 
+/// the geography icon for a region, or a river if one runs through it
+fn terrain_icon(assets: &Assets, world: &WorldState, region: Region) -> char {
+    if !world.contains(region) {
+        // beyond the edge of the world
+        match world
+            .geography(assets, region)
+            .and_then(|g| g.icon.as_ref())
+        {
+            Some(icon) => assets.get_icon(&icon.name).ch(),
+            None => ' ',
+        }
+    } else if world.get_water(region).kind == Hydrology::River {
+        assets.get_icon("map_terrain_water").ch()
+    } else {
+        world.get_icon(region)
+    }
+}
+
+/// the map icon for the roads in a region, joined up to the roads around it.
+/// <style> is the start of the icon name, which ends with the road's size
+fn road_icon(assets: &Assets, world: &WorldState, region: Region, style: &str) -> Option<char> {
+    let cur = world.get_road(region);
+    let curves = world.curve_indexes(region);
+    if cur.lanes_x == 0 && cur.lanes_y == 0 && curves.is_empty() {
+        return None;
+    }
+    let lanes = curves
+        .iter()
+        .map(|index| world.road_curves[*index].lanes)
+        .fold(cur.lanes_x.max(cur.lanes_y), u8::max);
+    let size = if lanes > 4 {
+        "large"
+    } else if lanes > 1 {
+        "medium"
+    } else {
+        "small"
+    };
+    let linked_y = |other: Region| {
+        (cur.lanes_y > 0 && world.get_road(other).lanes_y > 0) || world.curves_link(region, other)
+    };
+    let linked_x = |other: Region| {
+        (cur.lanes_x > 0 && world.get_road(other).lanes_x > 0) || world.curves_link(region, other)
+    };
+    Some(
+        assets
+            .get_icon(&format!("{}_{}", style, size))
+            .connected(
+                linked_y(Region::new(region.x, region.y - 1)),
+                linked_y(Region::new(region.x, region.y + 1)),
+                linked_x(Region::new(region.x - 1, region.y)),
+                linked_x(Region::new(region.x + 1, region.y)),
+            )
+            .ch(),
+    )
+}
+
+/// the map icon for the region's point of interest, if it has one
+fn poi_icon(assets: &Assets, world: &WorldState, region: Region) -> Option<char> {
+    let poi = world.get_point_of_interest(region)?;
+    let template = assets.get_point_of_interest(&poi.template)?;
+    Some(assets.get_icon(&template.icon.name).ch())
+}
+
+/// the icon for a region in the current map mode, with any point of interest
+/// drawn over the top
+fn map_icon(assets: &Assets, world: &WorldState, region: Region, mode: &MapMode) -> char {
+    if let Some(icon) = poi_icon(assets, world, region) {
+        return icon;
+    }
+    match mode {
+        MapMode::Hybrid => road_icon(assets, world, region, "map_hybrid")
+            .unwrap_or_else(|| terrain_icon(assets, world, region)),
+        MapMode::Street => road_icon(assets, world, region, "map_street").unwrap_or(' '),
+        MapMode::Terrain => terrain_icon(assets, world, region),
+        MapMode::Population => ' ',
+    }
+}
+
+/// the background for a region in the current map mode, a heat map of population
+/// density in population mode
+fn map_bg(world: &WorldState, region: Region, mode: &MapMode) -> TColor {
+    match mode {
+        MapMode::Population => {
+            let cold = TColor::new(32, 48, 128);
+            let hot = TColor::new(230, 64, 32);
+            let pop = world.get_pop(region).clamp(0.0, 1.0);
+            cold * (1.0 - pop) + hot * pop
+        }
+        _ => TColor::new(128, 128, 128),
+    }
+}
+
+fn mode_name(mode: &MapMode) -> &'static str {
+    match mode {
+        MapMode::Hybrid => "[M]ode:hybrid",
+        MapMode::Terrain => "[M]ode:terrain",
+        MapMode::Street => "[M]ode:street",
+        MapMode::Population => "[M]ode:population",
+    }
+}
+
+/// the point of interest in the region, or else the roads running through it, or
+/// else the nearest road. Shows the level instead when off the ground floor
+fn location(world: &WorldState, region: Region) -> String {
+    if region.z != 0 {
+        return region.level_name();
+    }
+    if let Some(poi) = world.get_point_of_interest(region) {
+        return poi.name.clone();
+    }
+    let names = world.road_names(region);
+    if !names.is_empty() {
+        return names.join(" / ");
+    }
+    match world.road_graph.nearest_road(region) {
+        Some(road) => {
+            let dx = road.x - region.x;
+            let dy = road.y - region.y;
+            let name = world.road_names(road).join(" / ");
+            let ns = if dy < 0 { "N" } else { "S" };
+            let ew = if dx < 0 { "W" } else { "E" };
+            format!("{}: {}{} {}{}", name, dy.abs(), ns, dx.abs(), ew)
+        }
+        None => region.level_name(),
+    }
+}
+
 pub fn draw_worldmap(
     mut console: &dyn Console,
     assets: &Assets,
@@ -40,66 +167,12 @@ pub fn draw_worldmap(
     );
     for ry in region.y - hh..=region.y + hh {
         for rx in region.x - hw..=region.x + hw {
-            let up = world.get_road(Region { x: rx, y: ry - 1 });
-            let down = world.get_road(Region { x: rx, y: ry + 1 });
-            let left = world.get_road(Region { x: rx - 1, y: ry });
-            let right = world.get_road(Region { x: rx + 1, y: ry });
-            let cur = world.get_road(Region { x: rx, y: ry });
-            match state.map_mode {
-                MapMode::Hybrid => {
-                    if cur.lanes_x == 0 && cur.lanes_y == 0 {
-                        ch = world.get_icon(Region { x: rx, y: ry });
-                    } else {
-                        let size = if cur.lanes_x > 4 || cur.lanes_y > 4 {
-                            "map_hybrid_large"
-                        } else if cur.lanes_x > 1 || cur.lanes_y > 1 {
-                            "map_hybrid_medium"
-                        } else {
-                            "map_hybrid_small"
-                        };
-                        ch = assets
-                            .get_icon(size)
-                            .connected(
-                                cur.lanes_y > 0 && up.lanes_y > 0,
-                                cur.lanes_y > 0 && down.lanes_y > 0,
-                                cur.lanes_x > 0 && left.lanes_x > 0,
-                                cur.lanes_x > 0 && right.lanes_x > 0,
-                            )
-                            .ch();
-                    }
-                }
-                MapMode::Street => {
-                    if cur.lanes_x == 0 && cur.lanes_y == 0 {
-                        ch = ' ';
-                    } else {
-                        let size = if cur.lanes_x > 4 || cur.lanes_y > 4 {
-                            "map_street_large"
-                        } else if cur.lanes_x > 1 || cur.lanes_y > 1 {
-                            "map_street_medium"
-                        } else {
-                            "map_street_small"
-                        };
-                        ch = assets
-                            .get_icon(size)
-                            .connected(
-                                cur.lanes_y > 0 && up.lanes_y > 0,
-                                cur.lanes_y > 0 && down.lanes_y > 0,
-                                cur.lanes_x > 0 && left.lanes_x > 0,
-                                cur.lanes_x > 0 && right.lanes_x > 0,
-                            )
-                            .ch();
-                    }
-                }
-                MapMode::Terrain => {
-                    ch = world.get_icon(Region { x: rx, y: ry });
-                }
-            }
-            // let cur_region = Region::new(rx, ry);
-            // let pop_col = 128; // ((world.get_pop(cur_region) * 128.0).floor()) as u8 + 64;
+            let cur_region = Region::new(rx, ry);
+            ch = map_icon(assets, world, cur_region, &state.map_mode);
             if region.y == ry && region.x == rx {
                 bg = TColor::new(128, 220, 128);
             } else {
-                bg = TColor::new(128, 128, 128);
+                bg = map_bg(world, cur_region, &state.map_mode);
             }
             console.put_char_ex(x, y, ch, fg, bg);
             x += 1;
@@ -108,11 +181,7 @@ pub fn draw_worldmap(
         y += 1;
     }
     console.set_alignment(TextAlignment::Center);
-    let mode_string: &str = match state.map_mode {
-        MapMode::Hybrid => "[M]ode:hybrid",
-        MapMode::Terrain => "[M]ode:terrain",
-        MapMode::Street => "[M]ode:street",
-    };
+    let mode_string = mode_name(&state.map_mode);
     console.print_rect(x + width / 2, base_y - 1, width, 1, mode_string);
 
     console.print_rect(
@@ -130,4 +199,157 @@ pub fn draw_worldmap(
             world.get_pop(region) * 10.0
         ),
     );
+    console.print_rect(x + width / 2, y + 1, width, 1, location(world, region));
+}
+
+/// whether the player has been to any of the regions in the cell starting at
+/// <region>, <zoom> regions on a side
+fn visited(world: &WorldState, explored: &Explored, region: Region, zoom: i32) -> bool {
+    (0..zoom).any(|y| {
+        (0..zoom).any(|x| explored.visited(world.wrap(Region::new(region.x + x, region.y + y))))
+    })
+}
+
+/// which cells of the world map view the player has been to, kept until the
+/// view moves or zooms, or the player goes somewhere new
+#[derive(Default)]
+pub struct MapFog {
+    /// the view's center and zoom, and how many regions had been visited
+    key: Option<(Region, i32, usize)>,
+    /// row by row, from the top left of the view
+    cells: Vec<bool>,
+}
+
+impl MapFog {
+    fn update(&mut self, world: &WorldState, explored: &Explored, view: MapView) {
+        let key = Some((view.center, view.zoom, explored.visited_count()));
+        if self.key == key {
+            return;
+        }
+        self.key = key;
+        self.cells.clear();
+        for y in -view.half_height..=view.half_height {
+            for x in -view.half_width..=view.half_width {
+                let region = view.region_at(x, y);
+                self.cells.push(visited(world, explored, region, view.zoom));
+            }
+        }
+    }
+}
+
+/// what the world map widget is showing, and for whom
+pub struct WorldMapFrame {
+    pub view: MapView,
+    /// the player's region, highlighted on the map
+    pub player: Region,
+    pub footer: String,
+}
+
+/// draws the world map widget over the map area, fogging regions the player
+/// hasn't been to
+pub fn draw_world_map_view(
+    mut console: &dyn Console,
+    assets: &Assets,
+    world: &WorldState,
+    explored: &Explored,
+    fog_cache: &mut MapFog,
+    state: &GameState,
+    frame: WorldMapFrame,
+) {
+    let WorldMapFrame {
+        view,
+        player,
+        footer,
+    } = frame;
+    reset_colors(&console);
+    let width = MAP_WIDTH as i32;
+    let height = MAP_HEIGHT as i32;
+    console.rect(0, 0, width, height, true, BackgroundFlag::Set);
+    draw_rect(console, 0, 0, width, height, assets.get_icon("line_single"));
+    let cx = width / 2;
+    let cy = 1 + view.half_height;
+    let fg = TColor::new(16, 16, 16);
+    let fog = TColor::new(24, 24, 32);
+    let (cursor_x, cursor_y) = view.cell_of(view.cursor);
+    let (player_x, player_y) = view.cell_of(player.on_level(0));
+    fog_cache.update(world, explored, view);
+    let mut cells = fog_cache.cells.iter();
+    for y in -view.half_height..=view.half_height {
+        for x in -view.half_width..=view.half_width {
+            let region = view.region_at(x, y);
+            let (ch, mut bg) = if cells.next() == Some(&true) {
+                (
+                    map_icon(assets, world, region, &state.map_mode),
+                    map_bg(world, region, &state.map_mode),
+                )
+            } else {
+                (' ', fog)
+            };
+            if (x, y) == (player_x, player_y) {
+                bg = TColor::new(128, 220, 128);
+            }
+            if (x, y) == (cursor_x, cursor_y) {
+                bg = TColor::new(220, 220, 128);
+            }
+            console.put_char_ex(cx + x, cy + y, ch, fg, bg);
+        }
+    }
+
+    // what's known about the region under the cursor
+    let region = view.cursor;
+    let info_y = height - 4;
+    console.set_alignment(TextAlignment::Left);
+    if explored.visited(world.wrap(region)) {
+        let (short, long) = match world
+            .geography(assets, region)
+            .and_then(|g| g.description.as_ref())
+        {
+            Some(description) => (description.short.as_str(), description.long.as_str()),
+            None => ("", ""),
+        };
+        console.print(
+            2,
+            info_y,
+            format!(
+                "{}, {}: {} - {} - pop: {:.1}",
+                region.x,
+                region.y,
+                short,
+                location(world, region),
+                world.get_pop(region) * 10.0
+            ),
+        );
+        console.print_rect(2, info_y + 1, width - 4, 2, long);
+        // how the place is getting on, as far as the world simulation knows
+        if let Some(settlement) = world
+            .canonical(region)
+            .and_then(|region| world.sim.settlement(region))
+        {
+            let residents: Vec<String> = settlement
+                .residents
+                .iter()
+                .map(|r| format!("{} {}", r.count, r.template))
+                .collect();
+            console.set_alignment(TextAlignment::Right);
+            console.print(
+                width - 3,
+                info_y,
+                format!(
+                    "{} - supplies: {:.0}%",
+                    residents.join(", "),
+                    settlement.supplies * 100.0
+                ),
+            );
+            console.set_alignment(TextAlignment::Left);
+        }
+    } else {
+        console.print(2, info_y, format!("{}, {}: unexplored", region.x, region.y));
+    }
+
+    console.set_alignment(TextAlignment::Center);
+    console.print(cx, 0, format!("World Map x{}", view.zoom));
+    console.set_alignment(TextAlignment::Left);
+    console.print(2, height - 1, mode_name(&state.map_mode));
+    console.set_alignment(TextAlignment::Right);
+    console.print(width - 3, height - 1, footer);
 }
@@ -16,12 +16,27 @@ mod system;
 mod util;
 use self::component::Pos;
 use self::component::*;
-use self::constants::{MAP_HEIGHT, MAP_WIDTH};
+use self::constants::{MAP_HEIGHT, MAP_WIDTH, WORLD_SIZE};
 use self::display::Display;
 use self::resource::*;
 use self::system::input::*;
 use self::system::*;
 
+/// the world's size and edge from the command line, in any order: a size in
+/// regions, and "wrap" to wrap the world around at the edges
+fn world_options() -> Result<(u32, WorldEdge), String> {
+    let mut size = WORLD_SIZE as u32;
+    let mut edge = WorldEdge::Border;
+    for arg in std::env::args().skip(1) {
+        match arg.parse::<u32>() {
+            _ if arg == "wrap" => edge = WorldEdge::Wrap,
+            Ok(value) if value > 0 => size = value,
+            _ => return Err(format!("unknown argument '{}'", arg)),
+        }
+    }
+    Ok((size, edge))
+}
+
 fn main() {
     let mut game = Game::new();
     component::init(&mut game);
@@ -33,8 +48,18 @@ fn main() {
     maps.init(Region::new(0, 0), constants::CHUNK_RADIUS);
     collisions.init(Region::new(0, 0), constants::CHUNK_RADIUS);
 
+    let (size, edge) = match world_options() {
+        Ok(options) => options,
+        Err(err) => {
+            println!("{}. Usage: scrapsrl [world size] [wrap]", err);
+            std::process::exit(1);
+        }
+    };
+
     game.add_resource(GameState::default());
-    game.add_resource(WorldState::default());
+    let mut world = WorldState::new(size);
+    world.edge = edge;
+    game.add_resource(world);
     game.add_resource(UserInput::default());
     game.add_resource(maps);
     game.add_resource(collisions);
@@ -51,7 +76,7 @@ fn main() {
             x: MAP_WIDTH / 2,
             y: MAP_HEIGHT / 2,
         })
-        .with(Region { x: 0, y: 0 })
+        .with(Region::new(0, 0))
         .with(MovePlan { x: 0, y: 0 })
         .with(IconRef::new("player_female"))
         .with(Colors {
@@ -59,6 +84,9 @@ fn main() {
             bg: Color::new(255, 255, 255),
         })
         .with(Character::default())
+        .with(Inventory::default())
+        .with(Energy::default())
+        .with(MovementMode::default())
         .with(Orientation::new(Direction::South))
         .build();
 
@@ -70,24 +98,35 @@ fn main() {
         .with(PreTick, "", &[])
         // handle user input first
         .with(UIInput, "ui_input", &[])
-        .with(SystemInput, "system_input", &["ui_input"])
+        .with(UIActions, "ui_actions", &["ui_input"])
+        .with(SaveGame, "save_game", &["ui_input"])
+        .with(SystemInput, "system_input", &["save_game"])
         .with(CursorInput, "cursor_input", &["system_input"])
         .with(PlayerInput, "player_input", &["cursor_input"])
-        .with(FallthroughInput, "fallthrough_input", &["player_input"])
+        .with(JournalInput, "journal_input", &["player_input"])
+        .with(WorldMapInput, "world_map_input", &["journal_input"])
+        .with(FallthroughInput, "fallthrough_input", &["world_map_input"])
         .with(WorldGen, "world_gen", &["fallthrough_input"])
+        // takes its census before new maps spawn anyone
+        .with(SimulateWorld, "simulate_world", &["world_gen"])
         .with(
             MapGenerator::new(),
             "map_gen",
-            &["fallthrough_input", "world_gen"],
+            &["fallthrough_input", "world_gen", "simulate_world"],
         )
         .with(CollisionSystem, "collision_system", &["map_gen"])
         // let AI decide what it wants to do
         .with(AI, "ai", &["collision_system"])
         // process AI and player actions
+        .with(
+            AutoTravel,
+            "auto_travel",
+            &["player_input", "collision_system"],
+        )
         .with(
             RegionSystem,
             "region",
-            &["ai", "player_input", "collision_system"],
+            &["ai", "player_input", "auto_travel", "collision_system"],
         )
         .with(
             BumpInteract,
@@ -95,8 +134,31 @@ fn main() {
             &["region", "collision_system"],
         )
         .with(Movement, "movement", &["region", "bump_interact"])
-        .with(PostTick, "", &["movement"])
-        .with(Notify, "interact_notify", &[])
+        .with(StoreEntities, "store_entities", &["region"])
+        // player interactions, one system per verb
+        .with(VerbMenu::default(), "verb_menu", &[])
+        .with(Examine::default(), "examine", &["verb_menu"])
+        .with(Talk::default(), "talk", &["verb_menu"])
+        .with(Attack::default(), "attack", &["verb_menu"])
+        .with(PickUp::default(), "pick_up", &["verb_menu"])
+        .with(Open::default(), "open", &["verb_menu"])
+        .with(Search::default(), "search", &["verb_menu"])
+        .with(
+            Notify::default(),
+            "interact_notify",
+            &["examine", "talk", "attack", "pick_up", "open", "search"],
+        )
+        .with(Quests::default(), "quests", &["interact_notify"])
+        .with(
+            ShowNotifications::default(),
+            "show_notifications",
+            &["quests"],
+        )
+        .with(
+            PostTick::default(),
+            "post_tick",
+            &["movement", "show_notifications"],
+        )
         .with(Stage, "game_stage", &[])
         .build();
 
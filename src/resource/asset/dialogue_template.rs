@@ -0,0 +1,190 @@
+use crate::component::Character;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// a character attribute that dialogue options can check
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub enum Attribute {
+    Strength,
+    Grace,
+    Toughness,
+    Intellect,
+    Wits,
+    Resolve,
+    Charisma,
+    Empathy,
+    Will,
+}
+
+impl Attribute {
+    pub fn of(self, character: &Character) -> u8 {
+        match self {
+            Attribute::Strength => character.strength(),
+            Attribute::Grace => character.grace(),
+            Attribute::Toughness => character.toughness(),
+            Attribute::Intellect => character.intellect(),
+            Attribute::Wits => character.wits(),
+            Attribute::Resolve => character.resolve(),
+            Attribute::Charisma => character.charisma(),
+            Attribute::Empathy => character.empathy(),
+            Attribute::Will => character.will(),
+        }
+    }
+}
+
+/// something that has to be true for a dialogue option to be offered
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum DialogueCondition {
+    /// the player's attribute is at least this high
+    MinAttribute(Attribute, u8),
+    /// the flag has been set
+    Flag(String),
+    /// the flag has not been set
+    NotFlag(String),
+    /// the player's standing with the faction is at least this high
+    MinReputation(String, i32),
+}
+
+/// something that happens when a dialogue option is chosen
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum DialogueEffect {
+    /// sets a world flag, which other dialogue can check
+    SetFlag(String),
+    /// gives the player an item, by name
+    GiveItem(String),
+    /// raises or lowers the player's standing with a faction
+    ChangeStanding(String, i32),
+    /// starts a quest, by quest template file stem
+    StartQuest(String),
+}
+
+/// what the player knows and is, as far as a conversation is concerned
+#[derive(Clone, Default)]
+pub struct DialogueContext {
+    pub character: Character,
+    pub flags: HashSet<String>,
+    pub reputation: HashMap<String, i32>,
+}
+
+impl DialogueContext {
+    pub fn allows(&self, condition: &DialogueCondition) -> bool {
+        match condition {
+            DialogueCondition::MinAttribute(attribute, min) => {
+                attribute.of(&self.character) >= *min
+            }
+            DialogueCondition::Flag(flag) => self.flags.contains(flag),
+            DialogueCondition::NotFlag(flag) => !self.flags.contains(flag),
+            DialogueCondition::MinReputation(faction, min) => {
+                self.reputation.get(faction).copied().unwrap_or(0) >= *min
+            }
+        }
+    }
+
+    /// keeps the context up to date with an effect, so later options see it
+    pub fn apply(&mut self, effect: &DialogueEffect) {
+        match effect {
+            DialogueEffect::SetFlag(flag) => {
+                self.flags.insert(flag.clone());
+            }
+            DialogueEffect::ChangeStanding(faction, change) => {
+                let standing = self.reputation.entry(faction.clone()).or_default();
+                *standing = (*standing + change).clamp(-100, 100);
+            }
+            DialogueEffect::GiveItem(_) | DialogueEffect::StartQuest(_) => {}
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DialogueOption {
+    /// what the player says
+    pub text: String,
+    #[serde(default)]
+    /// the node to go to next, or none to end the conversation
+    pub next: Option<String>,
+    #[serde(default)]
+    /// all of these must hold for the option to be offered
+    pub conditions: Vec<DialogueCondition>,
+    #[serde(default)]
+    pub effects: Vec<DialogueEffect>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DialogueNode {
+    /// what the NPC says
+    pub text: String,
+    #[serde(default)]
+    pub options: Vec<DialogueOption>,
+}
+
+/// A branching conversation, as a set of named nodes
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DialogueTemplate {
+    /// the node the conversation starts at
+    pub start: String,
+    pub nodes: HashMap<String, DialogueNode>,
+}
+
+impl DialogueTemplate {
+    /// the options at <node> that <context> allows
+    pub fn options<'a>(&'a self, node: &str, context: &DialogueContext) -> Vec<&'a DialogueOption> {
+        match self.nodes.get(node) {
+            Some(node) => node
+                .options
+                .iter()
+                .filter(|option| option.conditions.iter().all(|c| context.allows(c)))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// makes sure the start node and every option's next node exist
+    pub fn check(&self) -> Result<(), &'static str> {
+        if !self.nodes.contains_key(&self.start) {
+            return Err("dialogue start node does not exist");
+        }
+        for node in self.nodes.values() {
+            for option in &node.options {
+                if let Some(next) = &option.next {
+                    if !self.nodes.contains_key(next) {
+                        return Err("dialogue option leads to a node that does not exist");
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::TEMPLATE_DIR;
+    use crate::system::load_assets;
+    use std::fs::read_dir;
+
+    #[test]
+    fn dialogue_templates() {
+        // broken dialogues are left out when loading, so every shipped one has to make it in
+        let shipped = read_dir(format!("{}/dialogues", TEMPLATE_DIR))
+            .unwrap()
+            .count();
+        assert_eq!(load_assets().dialogue_len(), shipped);
+    }
+
+    #[test]
+    fn dialogue_conditions() {
+        let mut context = DialogueContext::default();
+        let flag = DialogueCondition::Flag("met".to_string());
+        assert!(!context.allows(&flag));
+        context.apply(&DialogueEffect::SetFlag("met".to_string()));
+        assert!(context.allows(&flag));
+        let liked = DialogueCondition::MinReputation("survivors".to_string(), 20);
+        context.apply(&DialogueEffect::ChangeStanding("survivors".to_string(), 25));
+        assert!(context.allows(&liked));
+        let charming = DialogueCondition::MinAttribute(Attribute::Charisma, 1);
+        assert!(!context.allows(&charming));
+        context.character.set_charisma(1);
+        assert!(context.allows(&charming));
+    }
+}
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
-use specs::World;
+use specs::world::EntitiesRes;
+use specs::{LazyUpdate, World};
 
 use crate::component::*;
 
@@ -8,10 +9,20 @@ pub struct EntityTemplate {
     brain: Option<AIBrain>,
     character: Option<Character>,
     colors: Option<Colors>,
+    #[serde(default)]
+    container: Option<Container>,
     description: Option<Description>,
+    #[serde(default)]
+    dialogue: Option<Dialogue>,
+    #[serde(default)]
+    faction: Option<Faction>,
     icon: Option<IconRef>,
+    #[serde(default)]
+    item: Option<Item>,
     notification: Option<NotificationInteraction>,
     solid: Option<Solid>,
+    #[serde(default)]
+    verbs: Option<Verbs>,
 }
 
 impl Default for EntityTemplate {
@@ -20,10 +31,15 @@ impl Default for EntityTemplate {
             brain: None,
             character: None,
             colors: None,
+            container: None,
             description: None,
+            dialogue: None,
+            faction: None,
             icon: None,
+            item: None,
             notification: None,
             solid: None,
+            verbs: None,
         }
     }
 }
@@ -35,10 +51,28 @@ impl EntityTemplate {
         EntityTemplateBuilder::new()
     }
 
+    /// whether entities from this template think and move for themselves
+    pub fn has_brain(&self) -> bool {
+        self.brain.is_some()
+    }
+
     pub fn to_world<'a>(&self, world: &'a mut World) -> impl Builder + 'a {
-        let mut builder = world.create_entity();
+        self.with_components(world.create_entity())
+    }
+
+    /// like to_world, but the entity is only built once the world is maintained,
+    /// so it can be used from inside a system
+    pub fn to_lazy<'a>(&self, lazy: &'a LazyUpdate, entities: &EntitiesRes) -> impl Builder + 'a {
+        self.with_components(lazy.create_entity(entities))
+    }
+
+    fn with_components<B: Builder>(&self, mut builder: B) -> B {
         if let Some(brain) = &self.brain {
-            builder = builder.with(brain.clone());
+            // brains need a plan to act on, and energy to act with
+            builder = builder
+                .with(brain.clone())
+                .with(MovePlan::default())
+                .with(Energy::default());
         }
         if let Some(character) = &self.character {
             builder = builder.with(character.clone());
@@ -46,18 +80,33 @@ impl EntityTemplate {
         if let Some(colors) = &self.colors {
             builder = builder.with(colors.clone());
         }
+        if let Some(container) = &self.container {
+            builder = builder.with(container.clone());
+        }
         if let Some(description) = &self.description {
             builder = builder.with(description.clone());
         }
+        if let Some(dialogue) = &self.dialogue {
+            builder = builder.with(dialogue.clone());
+        }
+        if let Some(faction) = &self.faction {
+            builder = builder.with(faction.clone());
+        }
         if let Some(icon) = &self.icon {
             builder = builder.with(icon.clone());
         }
+        if let Some(item) = &self.item {
+            builder = builder.with(item.clone());
+        }
         if let Some(notification) = &self.notification {
             builder = builder.with(notification.clone());
         }
         if self.solid.is_some() {
             builder = builder.with(Solid);
         }
+        if let Some(verbs) = &self.verbs {
+            builder = builder.with(verbs.clone());
+        }
         builder
     }
 }
@@ -107,6 +156,18 @@ impl EntityTemplateBuilder {
         self
     }
 
+    pub fn dialogue(&mut self, name: &str) -> &mut EntityTemplateBuilder {
+        self.template.dialogue = Some(Dialogue {
+            name: name.to_string(),
+        });
+        self
+    }
+
+    pub fn faction(&mut self, name: &str) -> &mut EntityTemplateBuilder {
+        self.template.faction = Some(Faction::new(name));
+        self
+    }
+
     pub fn icon(&mut self, name: String) -> &mut EntityTemplateBuilder {
         self.template.icon = Some(IconRef { name });
         self
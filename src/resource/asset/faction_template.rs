@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// standing of a faction toward its own members
+pub const SAME_FACTION_STANDING: i32 = 100;
+
+/// A group of NPCs that share their feelings about everyone else. Standings run
+/// from -100 (kill on sight) to 100 (trusted ally)
+#[derive(Clone, Serialize, Deserialize)]
+pub struct FactionTemplate {
+    /// display name of the faction
+    pub name: String,
+    #[serde(default)]
+    /// starting standing toward the player, until the player's reputation changes
+    pub reputation: i32,
+    #[serde(default)]
+    /// standing toward other factions by file stem. Factions not listed are neutral
+    pub standings: HashMap<String, i32>,
+}
+
+impl FactionTemplate {
+    /// standing toward the faction named <other>, where <own> is this faction's name
+    pub fn standing_toward(&self, own: &str, other: &str) -> i32 {
+        if own == other {
+            return SAME_FACTION_STANDING;
+        }
+        self.standings.get(other).copied().unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::system::load_assets;
+
+    #[test]
+    fn faction_standings() {
+        let assets = load_assets();
+        let factions = assets.get_factions();
+        for (name, template) in factions {
+            assert_eq!(template.standing_toward(name, name), SAME_FACTION_STANDING);
+            for other in template.standings.keys() {
+                assert!(factions.contains_key(other), "unknown faction {}", other);
+            }
+        }
+        let roaches = &factions["shockroaches"];
+        assert!(roaches.standing_toward("shockroaches", "survivors") < 0);
+        assert_eq!(roaches.standing_toward("shockroaches", "nobody"), 0);
+    }
+}
@@ -1,4 +1,5 @@
 use crate::component::*;
+use crate::resource::Hydrology;
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
@@ -38,6 +39,11 @@ impl Default for GroundCover {
     }
 }
 
+/// whether <value> falls within the inclusive <range>
+fn in_range(range: [f32; 2], value: f32) -> bool {
+    range[0] <= value && range[1] >= value
+}
+
 /// stub
 #[derive(Clone, Serialize, Deserialize)]
 pub struct GeographyTemplate {
@@ -49,10 +55,28 @@ pub struct GeographyTemplate {
     /// from 0.0 - 1.0
     pub population_range: [f32; 2],
     #[serde(default)]
+    /// lower and upper threshold of elevation in which the geography can occur.
+    /// If not given (here or by the parent), any elevation will do
+    pub elevation_range: Option<[f32; 2]>,
+    #[serde(default)]
+    /// lower and upper threshold of moisture in which the geography can occur
+    pub moisture_range: Option<[f32; 2]>,
+    #[serde(default)]
+    /// lower and upper threshold of temperature in which the geography can occur
+    pub temperature_range: Option<[f32; 2]>,
+    #[serde(default)]
+    /// kinds of water the geography can occur on. If not given, it occurs on
+    /// any land, including coasts and along rivers
+    pub hydrology: Option<Vec<Hydrology>>,
+    #[serde(default)]
+    /// marks the geography filling everything beyond the edge of a bordered
+    /// world. Border geographies never appear inside the world
+    pub border: bool,
+    #[serde(default)]
     /// list of structures that may appear in this map (density controlled elsewhere?)
     pub structures: Option<Vec<String>>,
     #[serde(default)]
-    /// description used when viewing on (unimplemented) world map
+    /// description shown for the region on the world map
     pub description: Option<Description>,
     #[serde(default)]
     /// a string referring to an icon by file stem, used on world map (icons/grass.ron = "grass" here)
@@ -77,6 +101,11 @@ impl Default for GeographyTemplate {
         GeographyTemplate {
             tags: None,
             population_range: [0.0, 1.0],
+            elevation_range: None,
+            moisture_range: None,
+            temperature_range: None,
+            hydrology: None,
+            border: false,
             structures: None,
             description: None,
             icon: None,
@@ -99,6 +128,12 @@ impl GeographyTemplate {
                 self.tags = Some(parent_tags.clone());
             }
         }
+        if self.hydrology.is_none() {
+            self.hydrology = parent.hydrology.clone();
+        }
+        self.elevation_range = self.elevation_range.or(parent.elevation_range);
+        self.moisture_range = self.moisture_range.or(parent.moisture_range);
+        self.temperature_range = self.temperature_range.or(parent.temperature_range);
         if let Some(ref parent_structures) = parent.structures {
             if let Some(ref mut structures) = self.structures {
                 for structure in parent_structures.iter() {
@@ -146,6 +181,28 @@ impl GeographyTemplate {
         self.parent = None;
     }
 
+    /// whether the geography can occur in a region with the given kind of water
+    pub fn fits_hydrology(&self, water: Hydrology) -> bool {
+        match &self.hydrology {
+            Some(kinds) => kinds.contains(&water),
+            None => !water.is_body(),
+        }
+    }
+
+    /// whether the geography can occur with the given population level
+    pub fn fits_population(&self, pop: f32) -> bool {
+        in_range(self.population_range, pop)
+    }
+
+    /// whether the geography can occur with the given elevation, moisture and
+    /// temperature
+    pub fn fits_climate(&self, elevation: f32, moisture: f32, temperature: f32) -> bool {
+        let fits = |range: Option<[f32; 2]>, value: f32| range.iter().all(|r| in_range(*r, value));
+        fits(self.elevation_range, elevation)
+            && fits(self.moisture_range, moisture)
+            && fits(self.temperature_range, temperature)
+    }
+
     /// length of structure types list
     pub fn structure_len(&self) -> usize {
         if let Some(structures) = &self.structures {
@@ -163,3 +220,52 @@ impl GeographyTemplate {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::system::load_assets;
+
+    #[test]
+    fn geography_hydrology() {
+        let assets = load_assets();
+        let templates = assets.get_geographies();
+        // every kind of region needs at least one geography to choose from
+        for water in &[
+            Hydrology::Dry,
+            Hydrology::Coast,
+            Hydrology::River,
+            Hydrology::Lake,
+            Hydrology::Sea,
+        ] {
+            assert!(
+                templates.iter().any(|(_, t)| t.fits_hydrology(*water)),
+                "no geography fits {:?}",
+                water
+            );
+        }
+    }
+
+    #[test]
+    fn geography_climate() {
+        let mut parent = GeographyTemplate {
+            moisture_range: Some([0.2, 0.8]),
+            temperature_range: Some([0.0, 0.5]),
+            ..GeographyTemplate::default()
+        };
+        let mut cold_forest = GeographyTemplate {
+            moisture_range: Some([0.5, 1.0]),
+            parent: Some("meadow".to_string()),
+            ..GeographyTemplate::default()
+        };
+        cold_forest.inherit(&mut parent);
+        // its own moisture range, and the parent's temperature range
+        assert_eq!(cold_forest.moisture_range, Some([0.5, 1.0]));
+        assert_eq!(cold_forest.temperature_range, Some([0.0, 0.5]));
+        assert!(cold_forest.fits_climate(0.5, 0.9, 0.2));
+        assert!(!cold_forest.fits_climate(0.5, 0.3, 0.2));
+        assert!(!cold_forest.fits_climate(0.5, 0.9, 0.7));
+        // without ranges anything will do
+        assert!(GeographyTemplate::default().fits_climate(1.0, 0.0, 1.0));
+    }
+}
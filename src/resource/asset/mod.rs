@@ -1,27 +1,41 @@
 /// Assets for game objects, to be serialized and deserialized from RON files
 pub mod builder;
+pub mod dialogue_template;
 pub mod entity_template;
+pub mod faction_template;
 pub mod geography_template;
 pub mod icons;
+pub mod poi_template;
+pub mod prefab;
+pub mod quest_template;
 pub mod structure_template;
 
 pub use builder::*;
+pub use dialogue_template::*;
 pub use entity_template::*;
+pub use faction_template::*;
 pub use geography_template::*;
 pub use icons::*;
+pub use poi_template::*;
+pub use prefab::*;
+pub use quest_template::*;
 pub use structure_template::*;
 
 use specs::{Component, VecStorage};
 use std::collections::HashMap;
 
-#[derive(Component)]
+#[derive(Clone, Component)]
 #[storage(VecStorage)]
 pub struct Assets {
+    dialogues: HashMap<String, DialogueTemplate>,
     entities: HashMap<String, EntityTemplate>,
+    factions: HashMap<String, FactionTemplate>,
     icons: HashMap<String, Icon>,
     icons_by_ch: HashMap<char, String>,
     structures: HashMap<String, StructureTemplate>,
     geographies: HashMap<String, GeographyTemplate>,
+    points_of_interest: HashMap<String, PoiTemplate>,
+    quests: HashMap<String, QuestTemplate>,
     default_icon: Icon,
     pub ready: bool,
 }
@@ -29,11 +43,15 @@ pub struct Assets {
 impl Default for Assets {
     fn default() -> Assets {
         Assets {
+            dialogues: HashMap::new(),
             entities: HashMap::new(),
+            factions: HashMap::new(),
             icons: HashMap::new(),
             icons_by_ch: HashMap::new(),
             structures: HashMap::new(),
             geographies: HashMap::new(),
+            points_of_interest: HashMap::new(),
+            quests: HashMap::new(),
             default_icon: Default::default(),
             ready: false,
         }
@@ -45,21 +63,47 @@ impl Assets {
         self.icons_by_ch.insert(icon.ch(), name.to_string());
         self.icons.insert(name.to_string(), icon);
     }
+    pub fn add_dialogue(&mut self, name: &str, template: DialogueTemplate) {
+        self.dialogues.insert(name.to_string(), template);
+    }
     pub fn add_entity(&mut self, name: &str, template: EntityTemplate) {
         self.entities.insert(name.to_string(), template);
     }
+    pub fn add_faction(&mut self, name: &str, template: FactionTemplate) {
+        self.factions.insert(name.to_string(), template);
+    }
     pub fn add_geography(&mut self, name: &str, template: GeographyTemplate) {
         self.geographies.insert(name.to_string(), template);
     }
-    pub fn add_structure(&mut self, name: &str, template: StructureTemplate) {
+    pub fn add_structure(&mut self, name: &str, mut template: StructureTemplate) {
+        template.name = name.to_string();
         self.structures.insert(name.to_string(), template);
     }
+    pub fn add_point_of_interest(&mut self, name: &str, template: PoiTemplate) {
+        self.points_of_interest.insert(name.to_string(), template);
+    }
+    pub fn add_quest(&mut self, name: &str, template: QuestTemplate) {
+        self.quests.insert(name.to_string(), template);
+    }
     pub fn len(&self) -> usize {
-        self.entities.len() + self.icons.len() + self.structures.len() + self.geographies.len()
+        self.dialogues.len()
+            + self.entities.len()
+            + self.factions.len()
+            + self.icons.len()
+            + self.structures.len()
+            + self.geographies.len()
+            + self.points_of_interest.len()
+            + self.quests.len()
+    }
+    pub fn dialogue_len(&self) -> usize {
+        self.dialogues.len()
     }
     pub fn entity_len(&self) -> usize {
         self.entities.len()
     }
+    pub fn faction_len(&self) -> usize {
+        self.factions.len()
+    }
     pub fn icon_len(&self) -> usize {
         self.icons.len()
     }
@@ -69,6 +113,12 @@ impl Assets {
     pub fn structure_len(&self) -> usize {
         self.structures.len()
     }
+    pub fn point_of_interest_len(&self) -> usize {
+        self.points_of_interest.len()
+    }
+    pub fn quest_len(&self) -> usize {
+        self.quests.len()
+    }
     pub fn get_geographies(&self) -> &HashMap<String, GeographyTemplate> {
         &self.geographies
     }
@@ -97,6 +147,34 @@ impl Assets {
         }
     }
 
+    pub fn get_dialogue(&self, name: &str) -> Option<&DialogueTemplate> {
+        self.dialogues.get(name)
+    }
+
+    pub fn get_entity(&self, name: &str) -> Option<&EntityTemplate> {
+        self.entities.get(name)
+    }
+
+    pub fn get_factions(&self) -> &HashMap<String, FactionTemplate> {
+        &self.factions
+    }
+
+    pub fn get_faction(&self, name: &str) -> Option<&FactionTemplate> {
+        self.factions.get(name)
+    }
+
+    pub fn get_points_of_interest(&self) -> &HashMap<String, PoiTemplate> {
+        &self.points_of_interest
+    }
+
+    pub fn get_point_of_interest(&self, name: &str) -> Option<&PoiTemplate> {
+        self.points_of_interest.get(name)
+    }
+
+    pub fn get_quest(&self, name: &str) -> Option<&QuestTemplate> {
+        self.quests.get(name)
+    }
+
     pub fn get_structure(&self, name: &str) -> Option<&StructureTemplate> {
         if let Some(structure) = self.structures.get(name) {
             Some(&structure)
@@ -0,0 +1,84 @@
+use crate::component::*;
+use serde::{Deserialize, Serialize};
+
+fn full_range() -> [f32; 2] {
+    [0.0, 1.0]
+}
+
+fn one() -> u8 {
+    1
+}
+
+/// entities of one template spawned at a point of interest
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PoiEntity {
+    /// an entity template by file stem (entities/shockroach.ron = "shockroach" here)
+    pub template: String,
+    #[serde(default = "one")]
+    pub min: u8,
+    #[serde(default = "one")]
+    pub max: u8,
+}
+
+/// A kind of notable place (a town, a camp, a hive), placed on the world map during
+/// world generation and forced into its region's map during map generation
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PoiTemplate {
+    /// what kind of place this is, shown when it has no name of its own
+    pub kind: String,
+    #[serde(default)]
+    /// names given to places of this kind, each used at most once per world
+    pub names: Vec<String>,
+    #[serde(default = "full_range")]
+    /// lower and upper threshold of population levels in which the place can occur
+    pub population_range: [f32; 2],
+    /// chance for any region in the population range to hold one
+    pub frequency: f32,
+    #[serde(default)]
+    /// most places of this kind in the world, if limited
+    pub max_count: Option<usize>,
+    /// a string referring to an icon by file stem, used on the world map
+    pub icon: IconRef,
+    #[serde(default)]
+    /// structures built in the region before any others
+    pub structures: Vec<String>,
+    #[serde(default)]
+    /// entities spawned in the region when its map is generated
+    pub entities: Vec<PoiEntity>,
+}
+
+impl PoiTemplate {
+    pub fn fits_population(&self, pop: f32) -> bool {
+        pop >= self.population_range[0] && pop <= self.population_range[1]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::system::load_assets;
+
+    #[test]
+    fn poi_templates() {
+        let assets = load_assets();
+        let templates = assets.get_points_of_interest();
+        for template in templates.values() {
+            // everything a place forces into its region has to exist
+            for name in &template.structures {
+                assert!(
+                    assets.get_structure(name).is_some(),
+                    "missing structure {}",
+                    name
+                );
+            }
+            for entity in &template.entities {
+                assert!(
+                    assets.get_entity(&entity.template).is_some(),
+                    "missing entity {}",
+                    entity.template
+                );
+                assert!(entity.min <= entity.max);
+            }
+        }
+        assert!(templates.len() >= 4);
+    }
+}
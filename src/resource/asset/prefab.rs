@@ -0,0 +1,184 @@
+//! Hand-authored structure layouts, stamped directly into a structure's grid rather
+//! than generated by the wfc system.
+use crate::component::Pos;
+use crate::util::Grid;
+
+/// An orientation for stamping a prefab: a number of clockwise quarter turns,
+/// optionally mirrored (left to right) before turning.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct PrefabOrientation {
+    pub quarter_turns: u8,
+    pub mirrored: bool,
+}
+
+impl PrefabOrientation {
+    /// lists all the orientations allowed by the given rotate/mirror settings
+    pub fn allowed(rotate: bool, mirror: bool) -> Vec<PrefabOrientation> {
+        let turns: &[u8] = if rotate { &[0, 1, 2, 3] } else { &[0] };
+        let mirrors: &[bool] = if mirror { &[false, true] } else { &[false] };
+        let mut list = Vec::new();
+        for mirrored in mirrors {
+            for quarter_turns in turns {
+                list.push(PrefabOrientation {
+                    quarter_turns: *quarter_turns,
+                    mirrored: *mirrored,
+                });
+            }
+        }
+        list
+    }
+}
+
+/// A rectangular grid of tile labels, parsed from an ASCII layout.
+#[derive(Clone)]
+pub struct Prefab {
+    cells: Grid<char>,
+}
+
+impl Prefab {
+    /// Parses a layout, one string per row.
+    /// # Errors
+    /// Returns an error if the layout is empty or its rows are of uneven length.
+    pub fn from_rows(rows: &[String]) -> Result<Prefab, &'static str> {
+        let height = rows.len();
+        let width = rows.first().map_or(0, |row| row.chars().count());
+        if width == 0 || height == 0 {
+            return Err("prefab layout is empty");
+        }
+        let mut cells: Grid<char> = Grid::with_dimensions(width, height);
+        for (y, row) in rows.iter().enumerate() {
+            if row.chars().count() != width {
+                return Err("prefab layout rows must all be the same length");
+            }
+            for (x, ch) in row.chars().enumerate() {
+                cells.unchecked_set(Pos::new(x, y), ch);
+            }
+        }
+        Ok(Prefab { cells })
+    }
+
+    pub fn width(&self) -> usize {
+        self.cells.width()
+    }
+
+    pub fn height(&self) -> usize {
+        self.cells.height()
+    }
+
+    /// the dimensions the prefab will have once turned to <orientation>
+    pub fn oriented_size(&self, orientation: PrefabOrientation) -> (usize, usize) {
+        match orientation.quarter_turns % 4 {
+            1 | 3 => (self.height(), self.width()),
+            _ => (self.width(), self.height()),
+        }
+    }
+
+    /// gets the label at <pos>, relative to the prefab's top left corner
+    pub fn get(&self, pos: Pos) -> Option<char> {
+        self.cells.maybe_get(pos).cloned()
+    }
+
+    /// checks whether the layout uses the label <ch> anywhere
+    pub fn contains(&self, ch: char) -> bool {
+        self.cells.iter().any(|cell| *cell == ch)
+    }
+
+    /// returns a copy of the prefab turned 90 degrees clockwise
+    pub fn rotated(&self) -> Prefab {
+        let (width, height) = (self.width(), self.height());
+        let mut cells: Grid<char> = Grid::with_dimensions(height, width);
+        for pos in self.cells.bounds.iter() {
+            let to = Pos::new(height - 1 - pos.y, pos.x);
+            cells.unchecked_set(to, *self.cells.unchecked_get(pos));
+        }
+        Prefab { cells }
+    }
+
+    /// returns a copy of the prefab flipped left to right
+    pub fn mirrored(&self) -> Prefab {
+        let width = self.width();
+        let mut cells: Grid<char> = Grid::with_dimensions(width, self.height());
+        for pos in self.cells.bounds.iter() {
+            let to = Pos::new(width - 1 - pos.x, pos.y);
+            cells.unchecked_set(to, *self.cells.unchecked_get(pos));
+        }
+        Prefab { cells }
+    }
+
+    /// returns a copy of the prefab in the given orientation
+    pub fn oriented(&self, orientation: PrefabOrientation) -> Prefab {
+        let mut prefab = if orientation.mirrored {
+            self.mirrored()
+        } else {
+            self.clone()
+        };
+        for _ in 0..orientation.quarter_turns % 4 {
+            prefab = prefab.rotated();
+        }
+        prefab
+    }
+}
+
+impl std::fmt::Debug for Prefab {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for row in self.cells.iter_rows() {
+            writeln!(f, "{}", row.iter().collect::<String>())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layout(rows: &[&str]) -> Prefab {
+        let rows: Vec<String> = rows.iter().map(|row| row.to_string()).collect();
+        Prefab::from_rows(&rows).unwrap()
+    }
+
+    #[test]
+    fn prefab_from_rows() {
+        let prefab = layout(&["ab", "cd", "ef"]);
+        assert_eq!(prefab.width(), 2);
+        assert_eq!(prefab.height(), 3);
+        assert_eq!(prefab.get(Pos::new(1, 2)), Some('f'));
+        assert_eq!(prefab.get(Pos::new(2, 0)), None);
+        assert!(prefab.contains('c'));
+        assert!(!prefab.contains('z'));
+    }
+
+    #[test]
+    fn prefab_from_bad_rows() {
+        assert!(Prefab::from_rows(&[]).is_err());
+        assert!(Prefab::from_rows(&["ab".to_string(), "c".to_string()]).is_err());
+    }
+
+    #[test]
+    fn prefab_rotated() {
+        let prefab = layout(&["ab", "cd", "ef"]).rotated();
+        assert_eq!(format!("{:?}", prefab), "eca\nfdb\n");
+        let prefab = prefab.rotated().rotated().rotated();
+        assert_eq!(format!("{:?}", prefab), "ab\ncd\nef\n");
+    }
+
+    #[test]
+    fn prefab_mirrored() {
+        let prefab = layout(&["abc", "def"]).mirrored();
+        assert_eq!(format!("{:?}", prefab), "cba\nfed\n");
+    }
+
+    #[test]
+    fn prefab_oriented() {
+        let prefab = layout(&["ab", "cd", "ef"]);
+        let orientation = PrefabOrientation {
+            quarter_turns: 1,
+            mirrored: true,
+        };
+        assert_eq!(prefab.oriented_size(orientation), (3, 2));
+        assert_eq!(format!("{:?}", prefab.oriented(orientation)), "fdb\neca\n");
+        assert_eq!(PrefabOrientation::allowed(true, true).len(), 8);
+        assert_eq!(PrefabOrientation::allowed(false, true).len(), 2);
+        assert_eq!(PrefabOrientation::allowed(false, false).len(), 1);
+    }
+}
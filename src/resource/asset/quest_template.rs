@@ -0,0 +1,114 @@
+use super::{Assets, DialogueEffect};
+use crate::component::Region;
+use serde::{Deserialize, Serialize};
+
+/// something the player has to do to finish a quest stage
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum Objective {
+    /// be in the region at x, y
+    ReachRegion(i32, i32),
+    /// be in a region holding a point of interest made from this template
+    ReachPoi(String),
+    /// carry an item, by name
+    Retrieve(String),
+    /// kill this many entities made from the entity template
+    Kill(String, u32),
+    /// talk to an NPC with this dialogue
+    Talk(String),
+}
+
+/// something that happened which objectives might be waiting for
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum QuestEvent {
+    /// an entity made from the template was killed by the player
+    Killed(String),
+    /// the player started a conversation using the dialogue
+    Talked(String),
+}
+
+/// where the player is and what they carry, checked against objectives every turn
+#[derive(Clone, Debug, Default)]
+pub struct QuestSituation {
+    pub region: Region,
+    /// the template of the point of interest in the player's region, if any
+    pub poi: Option<String>,
+    pub items: Vec<String>,
+}
+
+impl Objective {
+    /// how many times the objective has to be met
+    pub fn needed(&self) -> u32 {
+        match self {
+            Objective::Kill(_, count) => *count,
+            _ => 1,
+        }
+    }
+
+    /// whether <event> counts toward the objective
+    pub fn counts(&self, event: &QuestEvent) -> bool {
+        match (self, event) {
+            (Objective::Kill(template, _), QuestEvent::Killed(killed)) => template == killed,
+            (Objective::Talk(dialogue), QuestEvent::Talked(talked)) => dialogue == talked,
+            _ => false,
+        }
+    }
+
+    /// what the player has to do, for the quest log
+    pub fn describe(&self, assets: &Assets) -> String {
+        match self {
+            Objective::ReachRegion(x, y) => format!("Travel to region {}, {}", x, y),
+            Objective::ReachPoi(template) => format!(
+                "Find {}",
+                assets
+                    .get_point_of_interest(template)
+                    .map_or(template.as_str(), |poi| poi.kind.as_str())
+            ),
+            Objective::Retrieve(item) => format!("Get {}", item),
+            Objective::Kill(template, count) => {
+                format!("Kill {} {}", count, template.replace('_', " "))
+            }
+            Objective::Talk(dialogue) => format!("Talk to a {}", dialogue.replace('_', " ")),
+        }
+    }
+
+    /// whether the player's <situation> meets the objective
+    pub fn met_by(&self, situation: &QuestSituation) -> bool {
+        match self {
+            Objective::ReachRegion(x, y) => situation.region.x == *x && situation.region.y == *y,
+            Objective::ReachPoi(template) => situation.poi.as_ref() == Some(template),
+            Objective::Retrieve(item) => situation.items.contains(item),
+            _ => false,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct QuestStage {
+    /// what the player is told to do, shown in the quest log
+    pub description: String,
+    /// all of these must be met to move on to the next stage
+    pub objectives: Vec<Objective>,
+}
+
+/// A quest, as a series of stages finished one after another
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct QuestTemplate {
+    pub title: String,
+    pub description: String,
+    pub stages: Vec<QuestStage>,
+    #[serde(default)]
+    /// applied when the last stage is finished
+    pub rewards: Vec<DialogueEffect>,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::system::load_assets;
+
+    #[test]
+    fn quest_templates() {
+        for (name, template) in &load_assets().quests {
+            assert!(!template.stages.is_empty(), "{} has no stages", name);
+        }
+    }
+}
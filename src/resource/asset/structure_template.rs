@@ -1,8 +1,38 @@
-use crate::component::Description;
+use super::prefab::{Prefab, PrefabOrientation};
+use crate::component::{Description, Pos};
 use crate::resource::{Assets, Tile};
-use crate::util::Rect;
+use crate::util::{Grid, Rect};
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 
+/// number of times the wfc system is restarted before giving up on a structure
+const COLLAPSE_RETRIES: usize = 1000;
+
+/// Errors raised while generating a structure's interior.
+#[derive(Debug)]
+pub enum StructureError {
+    /// the wfc system could not satisfy the template's rules within the bounds
+    Contradiction {
+        template: String,
+        bounds: Rect<usize>,
+    },
+}
+
+impl std::fmt::Display for StructureError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            StructureError::Contradiction { template, bounds } => write!(
+                f,
+                "structure template '{}' could not be generated in {} ({}x{})",
+                template,
+                bounds,
+                bounds.width(),
+                bounds.height()
+            ),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum StructureConnectionType {
     Road,              // place connection facing the nearest road, if on the structure perimeter
@@ -86,6 +116,9 @@ pub struct StructureTile {
     weight: u32,
     #[serde(default)]
     allowed_neighbors: (HashSet<char>, HashSet<char>, HashSet<char>, HashSet<char>),
+    #[serde(default)]
+    /// pixels of this color in an example image are read as this tile
+    pub key_color: Option<(u8, u8, u8)>,
 }
 
 use std::collections::HashSet;
@@ -107,6 +140,7 @@ impl Default for StructureTile {
                 HashSet::new(),
                 HashSet::new(),
             ),
+            key_color: None,
         }
     }
 }
@@ -149,6 +183,14 @@ impl StructureTile {
         }
     }
 
+    /// whether the wfc system can place this tile: it must either never be chosen
+    /// (zero weight) or allow at least one neighbor on every side
+    fn is_placeable(&self) -> bool {
+        let (north, east, south, west) = &self.allowed_neighbors;
+        self.weight == 0
+            || !(north.is_empty() || east.is_empty() || south.is_empty() || west.is_empty())
+    }
+
     pub fn to_tile(&self, assets: &Assets) -> Tile {
         Tile {
             icon: assets.get_icon(&self.icon).ch(),
@@ -158,6 +200,7 @@ impl StructureTile {
             walkable: self.walkable,
             constructed: true,
             description: Description::new(&self.short_desc, &self.long_desc),
+            stairs: None,
         }
     }
 }
@@ -176,11 +219,28 @@ fn default_empty_connection_vec() -> Vec<StructureConnection> {
     Vec::new()
 }
 
+fn default_pattern_size() -> u32 {
+    3
+}
+
+fn default_floors() -> u8 {
+    1
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct StructureTemplate {
+    #[serde(skip)]
+    /// the name the template was registered under (its file stem)
+    pub name: String,
+    #[serde(default)]
+    /// size limits, including the perimeter. These are derived from the layout
+    /// for prefab structures, so they can be left out of prefab templates
     pub min_width: usize,
+    #[serde(default)]
     pub max_width: usize,
+    #[serde(default)]
     pub min_height: usize,
+    #[serde(default)]
     pub max_height: usize,
     /// perimeter is *inside* the bounds, so account for it in min/max properties
     /// a perimeter less than / equal to min_width or min_height WILL CRASH
@@ -195,17 +255,67 @@ pub struct StructureTemplate {
     #[serde(default = "default_empty_connection_vec")]
     /// a special instruction for connecting to roads, other structures, etc
     pub connect_to: Vec<StructureConnection>,
+    #[serde(default = "default_floors")]
+    /// number of storeys, counting the ground floor. Upper floors are generated
+    /// from the same template and joined by stairs
+    pub floors: u8,
+    #[serde(default)]
+    /// number of levels below the ground floor
+    pub basements: u8,
+    #[serde(default)]
+    /// a simpler structure to generate instead if this one can't be generated in
+    /// the space given. If that fails too, the structure is filled with floor
+    pub fallback: Option<String>,
     #[serde(default = "default_empty_string_vec")]
     /// nested structures that may be placed inside this structure
     pub interior_structures: Vec<String>,
     /// this contains all the tiles and rules for the structure to pass to the wfc system
     /// 'char' is an arbitrary character for convenient use in the template
     pub tiles: HashMap<char, StructureTile>,
+    #[serde(default)]
+    /// a hand-drawn interior layout, one string per row, labelled with the chars
+    /// from `tiles`. It is stamped directly into the structure (inside the perimeter)
+    /// instead of being generated
+    pub layout: Option<Vec<String>>,
+    #[serde(default)]
+    /// cells of the layout marked with this char are filled in by the wfc system
+    pub fill: Option<char>,
+    #[serde(default)]
+    /// an example interior, one string per row, labelled with the chars from
+    /// `tiles`. When present the wfc system learns its rules from the example
+    /// (the overlapping model) instead of using the tiles' allowed neighbors
+    pub example: Option<Vec<String>>,
+    #[serde(default)]
+    /// an image to use as the example instead, relative to the structure template
+    /// directory. Each pixel is matched to a tile by its key_color
+    pub example_image: Option<String>,
+    #[serde(default = "default_pattern_size")]
+    /// width and height of the patterns learned from the example
+    pub pattern_size: u32,
+    #[serde(default)]
+    /// the patterns learned from the example may be turned in 90 degree steps
+    pub pattern_rotate: bool,
+    #[serde(default)]
+    /// the patterns learned from the example may be flipped
+    pub pattern_mirror: bool,
+    #[serde(default)]
+    /// the layout may be turned in 90 degree steps when placed
+    pub rotate: bool,
+    #[serde(default)]
+    /// the layout may be flipped when placed
+    pub mirror: bool,
+    #[serde(skip)]
+    /// parsed version of the layout, built by init()
+    prefab: Option<Prefab>,
+    #[serde(skip)]
+    /// parsed version of the example, built by init() or load_example_image()
+    sample: Option<Prefab>,
 }
 
 impl Default for StructureTemplate {
     fn default() -> StructureTemplate {
         StructureTemplate {
+            name: "".to_string(),
             min_width: 3,
             max_width: 3,
             min_height: 3,
@@ -214,8 +324,22 @@ impl Default for StructureTemplate {
             building_slots: 1,
             perimeter_tile: None,
             connect_to: Vec::new(),
+            floors: default_floors(),
+            basements: 0,
+            fallback: None,
             interior_structures: Vec::new(),
             tiles: HashMap::new(),
+            layout: None,
+            fill: None,
+            example: None,
+            example_image: None,
+            pattern_size: default_pattern_size(),
+            pattern_rotate: false,
+            pattern_mirror: false,
+            rotate: false,
+            mirror: false,
+            prefab: None,
+            sample: None,
         }
     }
 }
@@ -224,6 +348,9 @@ use direction::*;
 impl StructureTemplate {
     /// does cleanup and reconciliation of allowed neighbors rules
     pub fn init(&mut self) {
+        self.init_prefab();
+        self.init_example();
+
         let mut floors: HashSet<char> = HashSet::new();
 
         // gather up all the floors, which can go next to most other tiles
@@ -320,6 +447,128 @@ impl StructureTemplate {
         */
     }
 
+    /// parses the layout, if there is one, and sizes the structure to fit it
+    fn init_prefab(&mut self) {
+        if let Some(rows) = &self.layout {
+            match Prefab::from_rows(rows) {
+                Ok(prefab) => {
+                    let border = self.perimeter * 2;
+                    let (short, long) = if prefab.width() < prefab.height() {
+                        (prefab.width(), prefab.height())
+                    } else {
+                        (prefab.height(), prefab.width())
+                    };
+                    if self.rotate {
+                        self.min_width = short + border;
+                        self.max_width = long + border;
+                        self.min_height = short + border;
+                        self.max_height = long + border;
+                    } else {
+                        self.min_width = prefab.width() + border;
+                        self.max_width = prefab.width() + border;
+                        self.min_height = prefab.height() + border;
+                        self.max_height = prefab.height() + border;
+                    }
+                    self.prefab = Some(prefab);
+                }
+                Err(err) => {
+                    println!("ignoring structure layout: {}", err);
+                }
+            }
+        }
+    }
+
+    /// parses the example, if there is one
+    fn init_example(&mut self) {
+        if let Some(rows) = &self.example {
+            match Prefab::from_rows(rows) {
+                Ok(sample) => self.sample = Some(sample),
+                Err(err) => println!("ignoring structure example: {}", err),
+            }
+        }
+    }
+
+    /// Reads the example image, if there is one, from <dir>. Each pixel becomes
+    /// the label of the tile with a matching key color.
+    /// # Errors
+    /// Returns an error if the image can't be read or has colors no tile is keyed to.
+    pub fn load_example_image(&mut self, dir: &str) -> Result<(), &'static str> {
+        use tcod::image::Image;
+        let path = match &self.example_image {
+            Some(file) => format!("{}/{}", dir, file),
+            None => return Ok(()),
+        };
+        let image = Image::from_file(path).map_err(|_| "could not read example image")?;
+        let (width, height) = image.get_size();
+        let sample = self.example_from_pixels(width, height, |x, y| {
+            let color = image.get_pixel(x, y);
+            (color.r, color.g, color.b)
+        })?;
+        self.sample = Some(sample);
+        Ok(())
+    }
+
+    /// labels each pixel of an example image <width> by <height> with the tile
+    /// keyed to its color, as given by <pixel>
+    fn example_from_pixels<F: Fn(i32, i32) -> (u8, u8, u8)>(
+        &self,
+        width: i32,
+        height: i32,
+        pixel: F,
+    ) -> Result<Prefab, &'static str> {
+        let mut rows: Vec<String> = Vec::new();
+        for y in 0..height {
+            let mut row = String::new();
+            for x in 0..width {
+                let color = pixel(x, y);
+                let label = self
+                    .tiles
+                    .iter()
+                    .find(|(_, tile)| tile.key_color == Some(color))
+                    .map(|(ch, _)| *ch)
+                    .ok_or("example image has a color not keyed to any tile")?;
+                row.push(label);
+            }
+            rows.push(row);
+        }
+        Prefab::from_rows(&rows)
+    }
+
+    /// the parsed example, if the structure has one
+    pub fn sample(&self) -> Option<&Prefab> {
+        self.sample.as_ref()
+    }
+
+    /// the parsed prefab layout, if the structure has one
+    pub fn prefab(&self) -> Option<&Prefab> {
+        self.prefab.as_ref()
+    }
+
+    /// the orientations the prefab layout may be placed in
+    pub fn prefab_orientations(&self) -> Vec<PrefabOrientation> {
+        PrefabOrientation::allowed(self.rotate, self.mirror)
+    }
+
+    /// chooses an orientation for a prefab structure given a sample between 0.0
+    /// and 1.0, along with the outer dimensions it needs (including the perimeter)
+    pub fn prefab_dimensions(&self, sample: f32) -> Option<(PrefabOrientation, (usize, usize))> {
+        use crate::util::choose;
+        let orientation = choose(&self.prefab_orientations(), sample)?;
+        Some((orientation, self.prefab_size(orientation)?))
+    }
+
+    /// the outer dimensions of a prefab structure placed in <orientation>
+    fn prefab_size(&self, orientation: PrefabOrientation) -> Option<(usize, usize)> {
+        let border = self.perimeter * 2;
+        let (w, h) = self.prefab()?.oriented_size(orientation);
+        Some((w + border, h + border))
+    }
+
+    /// checks whether a tile label marks a wfc-filled cell in the layout
+    pub fn is_fill(&self, label: char) -> bool {
+        self.fill == Some(label)
+    }
+
     /// builds a charmap of the structure's tiles
     pub fn get_charmap(&self) -> CharMap {
         let mut charmap: CharMap = HashMap::new();
@@ -346,6 +595,118 @@ impl StructureTemplate {
         PatternTable::from_vec(descs)
     }
 
+    /// Runs the wfc system over <bounds>, returning the chosen tile labels.
+    /// # Errors
+    /// Returns an error if the rules contradict each other every time they're tried.
+    pub fn collapse<R: Rng>(
+        &self,
+        bounds: Rect<usize>,
+        rng: &mut R,
+    ) -> Result<Grid<char>, StructureError> {
+        let labels = match self.sample() {
+            Some(sample) => self.collapse_overlapping(sample, bounds, rng),
+            None => self.collapse_tiled(bounds, rng),
+        };
+        labels.ok_or_else(|| StructureError::Contradiction {
+            template: self.name.clone(),
+            bounds,
+        })
+    }
+
+    /// collapses using the tiles' allowed neighbors
+    fn collapse_tiled<R: Rng>(&self, bounds: Rect<usize>, rng: &mut R) -> Option<Grid<char>> {
+        if self.tiles.is_empty() || !self.tiles.values().all(StructureTile::is_placeable) {
+            return None;
+        }
+        let stats = wfc::GlobalStats::new(self.get_pattern_table());
+        let mapchar = self.get_mapchar();
+        self.run_wfc(&stats, bounds, rng, |id| mapchar.get(&id).cloned())
+    }
+
+    /// collapses using patterns learned from the example
+    fn collapse_overlapping<R: Rng>(
+        &self,
+        sample: &Prefab,
+        bounds: Rect<usize>,
+        rng: &mut R,
+    ) -> Option<Grid<char>> {
+        use core::num::NonZeroU32;
+        use wfc::overlapping::OverlappingPatterns;
+        let size = wfc::Size::new(sample.width() as u32, sample.height() as u32);
+        let grid = grid_2d::Grid::new_fn(size, |coord| {
+            sample
+                .get(Pos::from(coord))
+                .unwrap_or_else(|| self.floor_label())
+        });
+        let pattern_size = NonZeroU32::new(self.pattern_size.max(1))?;
+        let patterns = OverlappingPatterns::new(grid, pattern_size, &self.sample_orientations());
+        let stats = patterns.global_stats();
+        self.run_wfc(&stats, bounds, rng, |id| {
+            Some(*patterns.pattern_top_left_value(id))
+        })
+    }
+
+    /// the orientations patterns learned from the example may be used in
+    fn sample_orientations(&self) -> Vec<wfc::Orientation> {
+        use wfc::Orientation::*;
+        match (self.pattern_rotate, self.pattern_mirror) {
+            (true, true) => wfc::orientation::ALL.to_vec(),
+            (true, false) => vec![Original, Clockwise90, Clockwise180, Clockwise270],
+            (false, true) => vec![Original, DiagonallyFlippedClockwise90],
+            (false, false) => vec![Original],
+        }
+    }
+
+    /// runs the wfc system with the given stats, turning the chosen pattern ids
+    /// into tile labels with <label>
+    fn run_wfc<R: Rng, F: Fn(wfc::PatternId) -> Option<char>>(
+        &self,
+        stats: &wfc::GlobalStats,
+        bounds: Rect<usize>,
+        rng: &mut R,
+        label: F,
+    ) -> Option<Grid<char>> {
+        use wfc::{retry::NumTimes, wrap::WrapNone, RunOwn};
+        let wfc_runner = RunOwn::new_wrap(bounds.to_wave_size(), stats, WrapNone, rng);
+        let wave = wfc_runner
+            .collapse_retrying(NumTimes(COLLAPSE_RETRIES), rng)
+            .ok()?;
+        let mut labels: Grid<char> = Grid::with_bounds(bounds);
+        for (coord, cell) in wave.grid().enumerate() {
+            let ch = cell
+                .chosen_pattern_id()
+                .ok()
+                .and_then(&label)
+                .unwrap_or_else(|| self.floor_label());
+            labels.unchecked_set(Pos::from(coord) + bounds.t_l, ch);
+        }
+        Some(labels)
+    }
+
+    /// the label used to fill a structure when generation fails: a floor tile
+    /// if there is one, or any tile otherwise (chosen consistently)
+    pub fn floor_label(&self) -> char {
+        let floors = self
+            .tiles
+            .iter()
+            .filter(|(_, tile)| tile.position == StructureTilePos::Floor)
+            .map(|(ch, _)| *ch)
+            .min();
+        floors
+            .or_else(|| self.tiles.keys().cloned().min())
+            .unwrap_or(' ')
+    }
+
+    /// a floor-only fill for <bounds>, the last resort when generation fails
+    pub fn floor_fill(&self, bounds: Rect<usize>) -> Grid<char> {
+        let mut labels: Grid<char> = Grid::with_bounds(bounds);
+        let floor = self.floor_label();
+        for pos in bounds.iter() {
+            labels.unchecked_set(pos, floor);
+        }
+        labels
+    }
+
     pub fn get_tile(&self, label: char) -> StructureTile {
         let default = StructureTile::default();
         let tile = self.tiles.get(&label).unwrap_or(&default);
@@ -353,7 +714,221 @@ impl StructureTemplate {
         tile.clone()
     }
 
-    pub fn fits_in(&self, rect: Rect<usize>) -> bool {
-        rect.width() >= self.min_width && rect.height() >= self.min_height
+    /// the levels the structure has besides the ground floor, from the lowest
+    /// basement to the highest floor
+    pub fn extra_levels(&self) -> Vec<i32> {
+        let basements = -i32::from(self.basements)..0;
+        let floors = 1..i32::from(self.floors);
+        basements.chain(floors).collect()
+    }
+
+    /// whether the structure fits in <rect>, with its prefab layout (if it has
+    /// one) in <orientation>
+    pub fn fits_in(&self, rect: Rect<usize>, orientation: PrefabOrientation) -> bool {
+        let (width, height) = self
+            .prefab_size(orientation)
+            .unwrap_or((self.min_width, self.min_height));
+        rect.width() >= width && rect.height() >= height
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::system::load_assets;
+    use rand::SeedableRng;
+    use rand_pcg::Pcg32;
+
+    /// every structure template shipped with the game, ready to collapse
+    fn load_templates() -> Vec<StructureTemplate> {
+        load_assets().structures.into_values().collect()
+    }
+
+    /// interior bounds for a structure with the given outer dimensions
+    fn interior(template: &StructureTemplate, width: usize, height: usize) -> Option<Rect<usize>> {
+        let border = template.perimeter * 2;
+        if width <= border || height <= border {
+            return None;
+        }
+        Some(Rect::new(
+            Pos::new(0, 0),
+            Pos::new(width - border - 1, height - border - 1),
+        ))
+    }
+
+    #[test]
+    /// every shipped template must collapse at its smallest and largest sizes, and at
+    /// a handful of random sizes in between
+    fn structure_templates_collapse() {
+        let rng = &mut Pcg32::seed_from_u64(0x5c4a_9500);
+        let templates = load_templates();
+        assert!(!templates.is_empty(), "no structure templates found");
+        let names: Vec<String> = templates.iter().map(|t| t.name.clone()).collect();
+        for template in templates {
+            // these are covered by structure_example_image
+            if template.example_image.is_some() {
+                continue;
+            }
+            if let Some(fallback) = &template.fallback {
+                assert!(
+                    names.contains(fallback),
+                    "structure template '{}' falls back to missing template '{}'",
+                    template.name,
+                    fallback
+                );
+            }
+            let mut sizes = vec![
+                (template.min_width, template.min_height),
+                (template.min_width, template.max_height),
+                (template.max_width, template.min_height),
+                (template.max_width, template.max_height),
+            ];
+            for _ in 0..6 {
+                sizes.push((
+                    rng.gen_range(template.min_width, template.max_width + 1),
+                    rng.gen_range(template.min_height, template.max_height + 1),
+                ));
+            }
+            for (width, height) in sizes {
+                if let Some(bounds) = interior(&template, width, height) {
+                    if let Err(err) = template.collapse(bounds, rng) {
+                        panic!("{}", err);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn structure_contradiction() {
+        // tiles that can't be next to anything, not even themselves
+        let mut template = StructureTemplate {
+            name: "impossible".to_string(),
+            ..StructureTemplate::default()
+        };
+        for label in &['x', 'y'] {
+            template.tiles.insert(
+                *label,
+                StructureTile {
+                    position: StructureTilePos::NoAuto,
+                    weight: 1,
+                    ..StructureTile::default()
+                },
+            );
+        }
+        template.init();
+        let rng = &mut Pcg32::seed_from_u64(1);
+        let bounds = Rect::new(Pos::new(2, 2), Pos::new(4, 3));
+        match template.collapse(bounds, rng) {
+            Err(StructureError::Contradiction { template, bounds }) => {
+                assert_eq!(template, "impossible");
+                assert_eq!(bounds.t_l, Pos::new(2, 2));
+                assert_eq!(bounds.b_r, Pos::new(4, 3));
+            }
+            Ok(_) => panic!("expected a contradiction"),
+        }
+        let fill = template.floor_fill(bounds);
+        assert!(fill.iter().all(|label| *label == 'x'));
+        assert_eq!(fill.bounds.t_l, Pos::new(2, 2));
+    }
+
+    #[test]
+    fn structure_example() {
+        // stripes one tile wide, which the overlapping model should reproduce
+        let mut template = StructureTemplate {
+            name: "stripes".to_string(),
+            example: Some(vec!["ab".to_string(), "ab".to_string()]),
+            pattern_size: 2,
+            ..StructureTemplate::default()
+        };
+        template.tiles.insert('a', StructureTile::default());
+        template.tiles.insert('b', StructureTile::default());
+        template.init();
+        assert!(template.sample().is_some());
+        let rng = &mut Pcg32::seed_from_u64(2);
+        let bounds = Rect::new(Pos::new(1, 1), Pos::new(8, 5));
+        let labels = template.collapse(bounds, rng).unwrap();
+        for pos in bounds.iter() {
+            let right = Pos::new(pos.x + 1, pos.y);
+            if let Some(next) = labels.maybe_get(right) {
+                assert_ne!(labels.unchecked_get(pos), next);
+            }
+            let below = Pos::new(pos.x, pos.y + 1);
+            if let Some(next) = labels.maybe_get(below) {
+                assert_eq!(labels.unchecked_get(pos), next);
+            }
+        }
+    }
+
+    #[test]
+    fn prefab_keeps_its_orientation() {
+        let mut template = StructureTemplate {
+            layout: Some(vec!["aaaa".to_string(), "aaaa".to_string()]),
+            rotate: true,
+            ..StructureTemplate::default()
+        };
+        template.tiles.insert('a', StructureTile::default());
+        template.init();
+        let (orientation, size) = template.prefab_dimensions(0.3).unwrap();
+        assert_eq!(orientation.quarter_turns, 1);
+        assert_eq!(size, (4, 6));
+        assert!(template.fits_in(Rect::new(Pos::new(0, 0), Pos::new(3, 5)), orientation));
+        // room for the layout the other way round isn't room for this one
+        assert!(!template.fits_in(Rect::new(Pos::new(0, 0), Pos::new(5, 3)), orientation));
+    }
+
+    #[test]
+    fn structure_example_pixels() {
+        let mut template = StructureTemplate::default();
+        let black = (0, 0, 0);
+        let white = (255, 255, 255);
+        for (label, color) in &[('a', black), ('b', white)] {
+            template.tiles.insert(
+                *label,
+                StructureTile {
+                    key_color: Some(*color),
+                    ..StructureTile::default()
+                },
+            );
+        }
+        let sample = template
+            .example_from_pixels(2, 2, |x, _| if x == 0 { black } else { white })
+            .unwrap();
+        assert_eq!(sample.get(Pos::new(0, 1)), Some('a'));
+        assert_eq!(sample.get(Pos::new(1, 0)), Some('b'));
+        assert!(template
+            .example_from_pixels(1, 1, |_, _| (9, 9, 9))
+            .is_err());
+    }
+
+    #[test]
+    /// the shipped image example, as the asset loader reads it
+    fn structure_example_image() {
+        let template = load_templates()
+            .into_iter()
+            .find(|t| t.name == "break_room")
+            .expect("no image example template");
+        let sample = template.sample().unwrap();
+        assert_eq!((sample.width(), sample.height()), (12, 8));
+        let rng = &mut Pcg32::seed_from_u64(3);
+        let sizes = [
+            (template.min_width, template.min_height),
+            (template.max_width, template.max_height),
+        ];
+        for (width, height) in sizes.iter() {
+            let bounds = interior(&template, *width, *height).unwrap();
+            if let Err(err) = template.collapse(bounds, rng) {
+                panic!("{}", err);
+            }
+        }
+    }
+
+    #[test]
+    fn structure_extra_levels() {
+        let mut template = StructureTemplate::default();
+        assert!(template.extra_levels().is_empty());
+        template.floors = 3;
+        template.basements = 2;
+        assert_eq!(template.extra_levels(), vec![-2, -1, 1, 2]);
     }
 }
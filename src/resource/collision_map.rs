@@ -44,6 +44,14 @@ impl CollisionMaps {
         }
     }
 
+    /// adds a collision map for a single region, used for upper floors and basements
+    pub fn init_level(&mut self, region: Region) {
+        self.maps.entry(region).or_insert_with(|| {
+            let bounds = Rect::new(Pos::new(0, 0), Pos::new(MAP_WIDTH, MAP_HEIGHT));
+            Grid::with_bounds(bounds)
+        });
+    }
+
     /// prunes maps in collection farther than <size> maps from <center> in a square
     pub fn prune(&mut self, center: Region, size: u8) {
         let s = i32::from(size);
@@ -0,0 +1,144 @@
+use crate::component::{AIBrain, Character, Container, Pos, Region, Template};
+use crate::resource::Assets;
+use serde::{Deserialize, Serialize};
+use specs::world::EntitiesRes;
+use specs::{Builder, LazyUpdate};
+use std::collections::{HashMap, HashSet};
+
+/// an entity that was unloaded along with its region. It's rebuilt from its
+/// template, with whatever about it can change since it was spawned: its
+/// character sheet, its brain and what it's carrying. Everything else, like its
+/// faction, energy or movement mode, comes back as the template has it
+#[derive(Clone, Deserialize, Serialize)]
+pub struct StoredEntity {
+    template: String,
+    pos: Pos,
+    region: Region,
+    #[serde(default)]
+    character: Option<Character>,
+    #[serde(default)]
+    brain: Option<AIBrain>,
+    #[serde(default)]
+    container: Option<Container>,
+}
+
+impl StoredEntity {
+    pub fn capture(
+        template: &Template,
+        pos: Pos,
+        region: Region,
+        character: Option<&Character>,
+        brain: Option<&AIBrain>,
+        container: Option<&Container>,
+    ) -> StoredEntity {
+        StoredEntity {
+            template: template.name.clone(),
+            pos,
+            region,
+            character: character.cloned(),
+            brain: brain.cloned(),
+            container: container.cloned(),
+        }
+    }
+
+    pub fn template(&self) -> &str {
+        &self.template
+    }
+
+    /// spawns the entity back in to <region>, wherever that's loaded, once the
+    /// world is maintained
+    pub fn restore(
+        &self,
+        region: Region,
+        assets: &Assets,
+        lazy: &LazyUpdate,
+        entities: &EntitiesRes,
+    ) {
+        let template = match assets.get_entity(&self.template) {
+            Some(template) => template,
+            None => {
+                println!("can't restore unknown entity {}", self.template);
+                return;
+            }
+        };
+        // later components replace the template's own
+        let mut builder = template
+            .to_lazy(lazy, entities)
+            .with(self.pos)
+            .with(region)
+            .with(Template::new(&self.template));
+        if let Some(character) = self.character {
+            builder = builder.with(character);
+        }
+        if let Some(brain) = &self.brain {
+            builder = builder.with(brain.clone());
+        }
+        if let Some(container) = &self.container {
+            builder = builder.with(container.clone());
+        }
+        builder.build();
+    }
+}
+
+/// entities from regions that aren't loaded right now, kept until their region
+/// comes back. Regions are kept as the world knows them (see WorldState::wrap)
+#[derive(Clone, Default, Deserialize, Serialize)]
+pub struct EntityStore {
+    /// regions whose map has spawned its entities already, and won't again
+    spawned: HashSet<Region>,
+    stored: HashMap<Region, Vec<StoredEntity>>,
+}
+
+impl EntityStore {
+    /// marks <region> as having spawned its entities, returning whether it's the
+    /// first time
+    pub fn first_visit(&mut self, region: Region) -> bool {
+        self.spawned.insert(region)
+    }
+
+    pub fn store(&mut self, entity: StoredEntity) {
+        self.stored.entry(entity.region).or_default().push(entity);
+    }
+
+    /// removes and returns everything stored in <region>
+    pub fn take(&mut self, region: Region) -> Vec<StoredEntity> {
+        self.stored.remove(&region).unwrap_or_default()
+    }
+
+    /// how many entities are waiting for their region to load
+    #[cfg(test)]
+    pub fn count(&self) -> usize {
+        self.stored.values().map(|entities| entities.len()).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stored_entities_wait_for_their_region() {
+        let mut store = EntityStore::default();
+        let region = Region::new(1, 2);
+        assert!(store.first_visit(region));
+        assert!(!store.first_visit(region));
+        let template = Template::new("shockroach");
+        store.store(StoredEntity::capture(
+            &template,
+            Pos::new(3, 4),
+            region,
+            Some(&Character::blank()),
+            None,
+            None,
+        ));
+        let text = ron::ser::to_string(&store).unwrap();
+        let mut loaded: EntityStore = ron::de::from_str(&text).unwrap();
+        assert_eq!(loaded.count(), 1);
+        assert!(loaded.take(Region::new(0, 0)).is_empty());
+        let taken = loaded.take(region);
+        assert_eq!(taken.len(), 1);
+        assert_eq!(taken[0].pos, Pos::new(3, 4));
+        assert_eq!(loaded.count(), 0);
+        assert!(!loaded.first_visit(region));
+    }
+}
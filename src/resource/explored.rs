@@ -0,0 +1,196 @@
+use crate::component::{Color, Pos, Region};
+use crate::constants::{MAP_HEIGHT, MAP_WIDTH};
+use crate::resource::Tile;
+use crate::util::Grid;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// what a tile looked like the last time the player saw it
+#[derive(Clone, Copy, Debug)]
+pub struct RememberedTile {
+    pub icon: char,
+    pub fg: Color,
+    pub bg: Color,
+}
+
+impl From<&Tile> for RememberedTile {
+    fn from(tile: &Tile) -> RememberedTile {
+        RememberedTile {
+            icon: tile.icon,
+            fg: tile.fg,
+            bg: tile.bg,
+        }
+    }
+}
+
+/// the player's memory of one region
+#[derive(Clone)]
+struct RegionMemory {
+    seen: Grid<bool>,
+    /// only known for tiles seen since the game was loaded
+    looks: Grid<Option<RememberedTile>>,
+}
+
+impl Default for RegionMemory {
+    fn default() -> RegionMemory {
+        RegionMemory {
+            seen: Grid::with_dimensions(MAP_WIDTH, MAP_HEIGHT),
+            looks: Grid::with_dimensions(MAP_WIDTH, MAP_HEIGHT),
+        }
+    }
+}
+
+/// which tiles the player has laid eyes on, per region, and which of them they
+/// can see right now. Filled in by the display as it computes FOV. Only which
+/// tiles were seen is saved, since maps are regenerated the same from the seed.
+/// Regions are kept as the world knows them (see WorldState::wrap), so going
+/// all the way around a wrapping world finds them again
+#[derive(Clone, Default, Deserialize, Serialize)]
+#[serde(from = "SavedExplored", into = "SavedExplored")]
+pub struct Explored {
+    regions: HashMap<Region, RegionMemory>,
+    /// the view can span several regions at once
+    visible: HashSet<(Region, Pos)>,
+    /// tiles already picked clean
+    searched: HashSet<(Region, Pos)>,
+}
+
+impl Explored {
+    /// forgets what was visible, ready for a new FOV
+    pub fn clear_visible(&mut self) {
+        self.visible.clear();
+    }
+
+    /// marks the <tile> at <pos> in <region> as visible, remembering how it looks
+    pub fn see(&mut self, region: Region, pos: Pos, tile: &Tile) {
+        let memory = self.regions.entry(region).or_default();
+        memory.seen.try_set(pos, true).ok();
+        memory.looks.try_set(pos, Some(tile.into())).ok();
+        self.visible.insert((region, pos));
+    }
+
+    pub fn seen(&self, region: Region, pos: Pos) -> bool {
+        self.regions
+            .get(&region)
+            .and_then(|memory| memory.seen.maybe_get(pos))
+            .cloned()
+            .unwrap_or(false)
+    }
+
+    /// whether the player has been to <region>
+    pub fn visited(&self, region: Region) -> bool {
+        self.regions.contains_key(&region)
+    }
+
+    /// how many regions the player has been to
+    pub fn visited_count(&self) -> usize {
+        self.regions.len()
+    }
+
+    pub fn visible(&self, region: Region, pos: Pos) -> bool {
+        self.visible.contains(&(region, pos))
+    }
+
+    /// marks <pos> in <region> as searched, returning whether it hadn't been yet
+    pub fn search(&mut self, region: Region, pos: Pos) -> bool {
+        self.searched.insert((region, pos))
+    }
+
+    /// how the tile at <pos> looked when last seen, if the player remembers
+    pub fn remembered(&self, region: Region, pos: Pos) -> Option<RememberedTile> {
+        self.regions
+            .get(&region)
+            .and_then(|memory| memory.looks.maybe_get(pos))
+            .cloned()
+            .unwrap_or(None)
+    }
+}
+
+/// seen tiles by region, as hex digits of four tiles each, row by row
+#[derive(Deserialize, Serialize)]
+struct SavedExplored {
+    regions: Vec<(Region, String)>,
+    #[serde(default)]
+    searched: Vec<(Region, Pos)>,
+}
+
+impl From<Explored> for SavedExplored {
+    fn from(explored: Explored) -> SavedExplored {
+        let tiles = (0..MAP_HEIGHT).flat_map(|y| (0..MAP_WIDTH).map(move |x| Pos::new(x, y)));
+        let regions = explored
+            .regions
+            .iter()
+            .map(|(region, memory)| {
+                let bits: Vec<bool> = tiles
+                    .clone()
+                    .map(|pos| *memory.seen.maybe_get(pos).unwrap_or(&false))
+                    .collect();
+                let hex: String = bits
+                    .chunks(4)
+                    .map(|chunk| {
+                        let digit = chunk
+                            .iter()
+                            .enumerate()
+                            .fold(0, |acc, (i, bit)| acc | (u32::from(*bit) << i));
+                        std::char::from_digit(digit, 16).unwrap()
+                    })
+                    .collect();
+                (*region, hex)
+            })
+            .collect();
+        SavedExplored {
+            regions,
+            searched: explored.searched.into_iter().collect(),
+        }
+    }
+}
+
+impl From<SavedExplored> for Explored {
+    fn from(saved: SavedExplored) -> Explored {
+        let mut explored = Explored::default();
+        for (region, hex) in saved.regions {
+            let mut memory = RegionMemory::default();
+            let bits = hex
+                .chars()
+                .flat_map(|c| {
+                    let digit = c.to_digit(16).unwrap_or(0);
+                    (0..4).map(move |i| digit & (1 << i) != 0)
+                })
+                .enumerate();
+            for (i, bit) in bits {
+                let pos = Pos::new(i % MAP_WIDTH, i / MAP_WIDTH);
+                memory.seen.try_set(pos, bit).ok();
+            }
+            explored.regions.insert(region, memory);
+        }
+        explored.searched = saved.searched.into_iter().collect();
+        explored
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seen_tiles_survive_saving() {
+        let mut explored = Explored::default();
+        let region = Region::new(2, -1);
+        explored.see(region, Pos::new(5, 3), &Tile::default());
+        assert!(explored.search(region, Pos::new(5, 3)));
+        explored.see(
+            region,
+            Pos::new(MAP_WIDTH - 1, MAP_HEIGHT - 1),
+            &Tile::default(),
+        );
+        let text = ron::ser::to_string(&explored).unwrap();
+        let loaded: Explored = ron::de::from_str(&text).unwrap();
+        assert!(loaded.seen(region, Pos::new(5, 3)));
+        assert!(loaded.seen(region, Pos::new(MAP_WIDTH - 1, MAP_HEIGHT - 1)));
+        assert!(!loaded.seen(region, Pos::new(4, 3)));
+        assert!(!loaded.clone().search(region, Pos::new(5, 3)));
+        assert!(!loaded.visible(region, Pos::new(5, 3)));
+        // appearances are picked up again from the map
+        assert!(loaded.remembered(region, Pos::new(5, 3)).is_none());
+    }
+}
@@ -0,0 +1,73 @@
+use crate::component::Pos;
+use crate::resource::InteractionMethod;
+use shrev::{EventChannel, ReaderId};
+use specs::{Entity, Resources};
+
+/// something that happened in the game, for any system that cares to hear about it
+#[derive(Clone, Debug)]
+pub enum GameEvent {
+    /// an entity took a step
+    Moved { entity: Entity },
+    /// the player walked into something they can interact with
+    Bumped { pos: Pos },
+    /// the player asked what they can do with whatever is at the position
+    MenuRequested(Pos),
+    /// the player used a verb on whatever is at the position
+    Interacted(InteractionMethod, Pos),
+    /// a character died, with the entity template it was spawned from if known
+    Killed { template: Option<String> },
+    /// the player crossed into another region or level
+    RegionChanged,
+    /// the player picked up or was given an item
+    PickedUp,
+    /// the player started a conversation using the dialogue
+    Talked(String),
+    /// something the player should be told about
+    Notified { title: String, body: String },
+}
+
+impl GameEvent {
+    /// the position the player wants to use one of <methods> on, if this is such
+    /// a request. Bumping into something counts as checking it
+    pub fn wants(&self, methods: &[InteractionMethod]) -> Option<Pos> {
+        match self {
+            GameEvent::Interacted(method, pos) if methods.contains(method) => Some(*pos),
+            GameEvent::Bumped { pos, .. } if methods.contains(&InteractionMethod::Check) => {
+                Some(*pos)
+            }
+            _ => None,
+        }
+    }
+}
+
+pub type GameEvents = EventChannel<GameEvent>;
+
+/// registers a reader for game events, for use in System::setup. Readers only see
+/// events written after they subscribe
+pub fn subscribe(res: &mut Resources) -> ReaderId<GameEvent> {
+    res.entry::<GameEvents>()
+        .or_insert_with(GameEvents::default)
+        .register_reader()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use specs::World;
+
+    #[test]
+    fn interaction_requests() {
+        let mut world = World::new();
+        let mut reader = subscribe(&mut world.res);
+        let pos = Pos::new(3, 4);
+        let mut events = world.write_resource::<GameEvents>();
+        events.single_write(GameEvent::Bumped { pos });
+        events.single_write(GameEvent::Interacted(InteractionMethod::Open, pos));
+        let talk: Vec<Pos> = events
+            .read(&mut reader)
+            .filter_map(|e| e.wants(&[InteractionMethod::Check, InteractionMethod::Talk]))
+            .collect();
+        // bumping into something checks it, but only verbs that were asked for count
+        assert_eq!(talk, vec![pos]);
+    }
+}
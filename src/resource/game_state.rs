@@ -5,6 +5,8 @@ pub enum GameStage {
     LoadingAssets,
     Initializing,
     Playing,
+    /// the player has been killed. Time stands still until a save is loaded
+    Dead,
 }
 
 #[derive(PartialEq, Eq)]
@@ -17,11 +19,14 @@ pub enum MapMode {
     Terrain,
     Street,
     Hybrid,
+    /// population density as a heat map
+    Population,
 }
 
 /// game state collects a bunch of globally needed data and coordination info in one
 /// place. Doesn't make a lot of sense to make these individual resources, causes too
-/// much boilerplate in Systems
+/// much boilerplate in Systems. These are modes the game is in, looked at every
+/// frame; things that happen go out as GameEvents instead
 pub struct GameState {
     pub stage: GameStage,
     /// if true, game will close on next game loop pass
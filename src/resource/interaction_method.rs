@@ -0,0 +1,47 @@
+use crate::component::{ATTACK_COST, INTERACT_COST, SEARCH_COST};
+use serde::{Deserialize, Serialize};
+
+/// a verb the player can use on an entity or tile
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InteractionMethod {
+    /// check the object (do default action, such as display its notifcation or menu)
+    Check,
+    /// look the object over
+    Examine,
+    /// open a container and take what's inside
+    Open,
+    /// search the ground for anything useful
+    Search,
+    /// start a conversation
+    Talk,
+    /// operate the object
+    Use,
+    Attack,
+    PickUp,
+}
+
+impl InteractionMethod {
+    pub fn name(self) -> &'static str {
+        match self {
+            InteractionMethod::Check => "Check",
+            InteractionMethod::Examine => "Examine",
+            InteractionMethod::Open => "Open",
+            InteractionMethod::Search => "Search",
+            InteractionMethod::Talk => "Talk",
+            InteractionMethod::Use => "Use",
+            InteractionMethod::Attack => "Attack",
+            InteractionMethod::PickUp => "Pick up",
+        }
+    }
+
+    /// how much energy using the verb takes
+    pub fn cost(self) -> i32 {
+        match self {
+            // looking things over is free
+            InteractionMethod::Examine => 0,
+            InteractionMethod::Search => SEARCH_COST,
+            InteractionMethod::Attack => ATTACK_COST,
+            _ => INTERACT_COST,
+        }
+    }
+}
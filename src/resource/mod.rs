@@ -1,17 +1,31 @@
 mod asset;
 mod collision_map;
+mod entity_store;
+mod explored;
+mod game_event;
 mod game_state;
-mod interaction_target;
+mod interaction_method;
+mod quest_log;
 mod region_maps;
+mod road_graph;
+mod travel;
 mod ui;
 mod user_input;
+mod world_sim;
 mod world_state;
 
 pub use asset::*;
 pub use collision_map::*;
+pub use entity_store::*;
+pub use explored::*;
+pub use game_event::*;
 pub use game_state::*;
-pub use interaction_target::*;
+pub use interaction_method::*;
+pub use quest_log::*;
 pub use region_maps::*;
+pub use road_graph::*;
+pub use travel::*;
 pub use ui::*;
 pub use user_input::*;
+pub use world_sim::*;
 pub use world_state::*;
@@ -0,0 +1,166 @@
+use crate::resource::{Assets, QuestEvent, QuestSituation, QuestTemplate};
+use serde::{Deserialize, Serialize};
+
+/// how far along the player is with one quest
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct QuestProgress {
+    /// the quest template, by file stem
+    pub quest: String,
+    /// index of the current stage, or the stage count when finished
+    pub stage: usize,
+    /// how many times each of the current stage's objectives has been met
+    pub counts: Vec<u32>,
+}
+
+/// a change in a quest worth telling the player about
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum QuestUpdate {
+    Advanced(String),
+    Finished(String),
+}
+
+impl QuestProgress {
+    pub fn new(quest: &str) -> QuestProgress {
+        QuestProgress {
+            quest: quest.to_string(),
+            stage: 0,
+            counts: Vec::new(),
+        }
+    }
+
+    pub fn finished(&self, template: &QuestTemplate) -> bool {
+        self.stage >= template.stages.len()
+    }
+
+    /// counts <events> and <situation> toward the current stage's objectives,
+    /// moving on to the next stage whenever all of them are met
+    pub fn advance(
+        &mut self,
+        template: &QuestTemplate,
+        events: &[QuestEvent],
+        situation: &QuestSituation,
+    ) -> Option<QuestUpdate> {
+        let start = self.stage;
+        while let Some(stage) = template.stages.get(self.stage) {
+            self.counts.resize(stage.objectives.len(), 0);
+            for (objective, count) in stage.objectives.iter().zip(self.counts.iter_mut()) {
+                if objective.met_by(situation) {
+                    *count = objective.needed();
+                }
+                // events only count toward the stage that was current when they happened
+                if self.stage == start {
+                    *count += events.iter().filter(|e| objective.counts(e)).count() as u32;
+                }
+                *count = (*count).min(objective.needed());
+            }
+            let done = stage
+                .objectives
+                .iter()
+                .zip(self.counts.iter())
+                .all(|(objective, count)| *count >= objective.needed());
+            if !done {
+                break;
+            }
+            self.stage += 1;
+            self.counts.clear();
+        }
+        if self.stage == start {
+            None
+        } else if self.finished(template) {
+            Some(QuestUpdate::Finished(self.quest.clone()))
+        } else {
+            Some(QuestUpdate::Advanced(self.quest.clone()))
+        }
+    }
+}
+
+/// every quest the player has taken on
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct QuestLog {
+    quests: Vec<QuestProgress>,
+}
+
+impl QuestLog {
+    /// starts the quest, returning false if it was already started
+    pub fn start(&mut self, quest: &str) -> bool {
+        if self.get(quest).is_some() {
+            return false;
+        }
+        self.quests.push(QuestProgress::new(quest));
+        true
+    }
+
+    pub fn get(&self, quest: &str) -> Option<&QuestProgress> {
+        self.quests.iter().find(|progress| progress.quest == quest)
+    }
+
+    pub fn quests(&self) -> &[QuestProgress] {
+        &self.quests
+    }
+
+    /// counts <events> and the player's <situation> toward every unfinished quest
+    pub fn update(
+        &mut self,
+        assets: &Assets,
+        events: &[QuestEvent],
+        situation: &QuestSituation,
+    ) -> Vec<QuestUpdate> {
+        let mut updates = Vec::new();
+        for progress in &mut self.quests {
+            let template = match assets.get_quest(&progress.quest) {
+                Some(template) => template,
+                None => continue,
+            };
+            if progress.finished(template) {
+                continue;
+            }
+            if let Some(update) = progress.advance(template, events, situation) {
+                updates.push(update);
+            }
+        }
+        updates
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resource::{Objective, QuestStage};
+
+    #[test]
+    fn quest_progress() {
+        let template = QuestTemplate {
+            title: "Pest Control".to_string(),
+            description: String::new(),
+            stages: vec![
+                QuestStage {
+                    description: "Find the hive".to_string(),
+                    objectives: vec![Objective::ReachPoi("hive".to_string())],
+                },
+                QuestStage {
+                    description: "Kill two roaches".to_string(),
+                    objectives: vec![Objective::Kill("roach".to_string(), 2)],
+                },
+            ],
+            rewards: Vec::new(),
+        };
+        let kill = [QuestEvent::Killed("roach".to_string())];
+        let mut situation = QuestSituation::default();
+        let mut progress = QuestProgress::new("pest_control");
+
+        // kills don't count before their stage
+        assert_eq!(progress.advance(&template, &kill, &situation), None);
+        situation.poi = Some("hive".to_string());
+        assert_eq!(
+            progress.advance(&template, &kill, &situation),
+            Some(QuestUpdate::Advanced("pest_control".to_string()))
+        );
+        assert_eq!(progress.counts, vec![0]);
+        assert_eq!(progress.advance(&template, &kill, &situation), None);
+        assert_eq!(
+            progress.advance(&template, &kill, &situation),
+            Some(QuestUpdate::Finished("pest_control".to_string()))
+        );
+        assert!(progress.finished(&template));
+    }
+}
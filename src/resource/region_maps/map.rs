@@ -1,4 +1,3 @@
-use super::iterators::RegionMapIter;
 use super::{Tile, HEIGHT, WIDTH};
 use crate::component::Pos;
 use crate::util::{Grid, Rect};
@@ -80,13 +79,6 @@ impl RegionMap {
         };
     }
 
-    pub fn iter(&self) -> RegionMapIter<'_> {
-        RegionMapIter {
-            map: self,
-            cur: [0, 0],
-        }
-    }
-
     #[allow(unused)]
     pub fn bounding_rect(&self) -> Rect<usize> {
         self.grid.bounds
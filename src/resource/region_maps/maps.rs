@@ -1,5 +1,6 @@
 use super::RegionMap;
-use crate::component::Region;
+use crate::component::{Pos, Region};
+use crate::resource::Tile;
 use crate::util::Rect;
 use specs::{Component, VecStorage};
 use std::collections::hash_map::IterMut;
@@ -48,6 +49,19 @@ impl RegionMaps {
         }
     }
 
+    /// the tile at <pos> in <region>, if its map has been generated
+    pub fn get_tile(&self, region: Region, pos: Pos) -> Option<&Tile> {
+        self.maps
+            .get(&region)
+            .filter(|map| map.populated)
+            .and_then(|map| map.get(pos))
+    }
+
+    /// every region with a map in play, generated or not
+    pub fn regions(&self) -> impl Iterator<Item = Region> + '_ {
+        self.maps.keys().cloned()
+    }
+
     /// checks whether a map is in play
     pub fn has(&self, region: Region) -> bool {
         self.maps.get(&region).is_some()
@@ -61,14 +75,6 @@ impl RegionMaps {
         }
     }
 
-    pub fn populated(&self) -> bool {
-        let mut result = true;
-        for (_, map) in self.maps.iter() {
-            result = result && map.populated
-        }
-        result
-    }
-
     /// prunes maps in collection farther than <size> maps from <center> in a square
     pub fn prune(&mut self, center: Region, size: u8) {
         let s = i32::from(size);
@@ -91,6 +97,19 @@ impl RegionMaps {
         }
     }
 
+    /// puts a generated map in play, for a region on the ground or an upper floor
+    /// or basement
+    pub fn insert(&mut self, region: Region, map: RegionMap) {
+        self.maps.insert(region, map);
+    }
+
+    /// removes the upper floors and basements above and below <region>, so they
+    /// can be regenerated along with it
+    pub fn clear_levels(&mut self, region: Region) {
+        self.maps
+            .retain(|other, _| other.z == 0 || other.x != region.x || other.y != region.y);
+    }
+
     pub fn iter_mut(&mut self) -> IterMut<'_, Region, RegionMap> {
         self.maps.iter_mut()
     }
@@ -1,5 +1,22 @@
 use crate::component::{Color, Description};
 
+/// stairs leading to the level above or below
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Stairs {
+    Up,
+    Down,
+}
+
+impl Stairs {
+    /// the change in level from taking the stairs
+    pub fn level_change(self) -> i32 {
+        match self {
+            Stairs::Up => 1,
+            Stairs::Down => -1,
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct Tile {
     pub icon: char,
@@ -11,6 +28,8 @@ pub struct Tile {
     /// used to prevent overlapping structures during generation
     pub constructed: bool,
     pub description: Description,
+    /// set if the tile leads to another level
+    pub stairs: Option<Stairs>,
 }
 
 impl Default for Tile {
@@ -23,6 +42,7 @@ impl Default for Tile {
             walkable: true,
             constructed: false,
             description: Description::default(),
+            stairs: None,
         }
     }
 }
@@ -45,6 +65,7 @@ impl Tile {
             walkable,
             constructed,
             description,
+            stairs: None,
         }
     }
 
@@ -57,5 +78,6 @@ impl Tile {
         self.walkable = other.walkable;
         self.constructed = other.constructed;
         self.description = other.description.clone();
+        self.stairs = other.stairs;
     }
 }
@@ -0,0 +1,848 @@
+//! A graph of the world's roads, built from the per-region road map. Nodes sit at
+//! intersections and dead ends, and edges follow straight stretches of road
+//! between them.
+use rand::prelude::*;
+use rand_pcg::Pcg32;
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+
+use crate::component::Region;
+use crate::resource::{WorldEdge, WorldState};
+
+/// roads with at least this many lanes are named as highways
+const HIGHWAY_LANES: u8 = 4;
+/// highways with at least this many lanes are interstates
+const INTERSTATE_LANES: u8 = 6;
+/// how many times to look for an unused name before settling for a numbered one
+const NAME_TRIES: usize = 12;
+
+const STREET_NAMES: [&str; 32] = [
+    "Oak",
+    "Maple",
+    "Elm",
+    "Pine",
+    "Cedar",
+    "Birch",
+    "Walnut",
+    "Chestnut",
+    "Willow",
+    "Main",
+    "Church",
+    "Mill",
+    "Park",
+    "Lake",
+    "Hill",
+    "River",
+    "Spring",
+    "Market",
+    "Union",
+    "Washington",
+    "Lincoln",
+    "Jefferson",
+    "Franklin",
+    "Madison",
+    "Highland",
+    "Sunset",
+    "Ridge",
+    "Meadow",
+    "Forest",
+    "Valley",
+    "Railroad",
+    "School",
+];
+const EAST_WEST_SUFFIXES: [&str; 4] = ["Street", "Road", "Lane", "Drive"];
+const NORTH_SOUTH_SUFFIXES: [&str; 4] = ["Avenue", "Boulevard", "Way", "Parkway"];
+
+/// the direction a stretch of road runs in
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum RoadAxis {
+    /// east to west, following lanes_x
+    Horizontal,
+    /// north to south, following lanes_y
+    Vertical,
+}
+
+impl RoadAxis {
+    /// the next region along the axis, heading east or south
+    fn next(self, region: Region) -> Region {
+        match self {
+            RoadAxis::Horizontal => Region::new(region.x + 1, region.y),
+            RoadAxis::Vertical => Region::new(region.x, region.y + 1),
+        }
+    }
+
+    /// the previous region along the axis, heading west or north
+    fn prev(self, region: Region) -> Region {
+        match self {
+            RoadAxis::Horizontal => Region::new(region.x - 1, region.y),
+            RoadAxis::Vertical => Region::new(region.x, region.y - 1),
+        }
+    }
+
+    fn lanes(self, world: &WorldState, region: Region) -> u8 {
+        let road = world.get_road(region);
+        match self {
+            RoadAxis::Horizontal => road.lanes_x,
+            RoadAxis::Vertical => road.lanes_y,
+        }
+    }
+
+    /// whether the road along this axis carries on from <region> to the next one
+    fn linked(self, world: &WorldState, region: Region) -> bool {
+        self.lanes(world, region) > 0 && self.lanes(world, self.next(region)) > 0
+    }
+}
+
+/// a road running freely across the world rather than along the grid, as a
+/// quadratic bezier curve. Points are measured in regions, so the middle of
+/// region (0, 0) is at (0.5, 0.5)
+#[derive(Clone, Debug)]
+pub struct RoadCurve {
+    /// start, control and end points
+    pub points: [[f32; 2]; 3],
+    pub lanes: u8,
+    pub name: String,
+}
+
+impl RoadCurve {
+    /// the point a fraction <t> of the way along the curve
+    pub fn at(&self, t: f32) -> [f32; 2] {
+        let [a, b, c] = self.points;
+        let u = 1.0 - t;
+        [
+            u * u * a[0] + 2.0 * u * t * b[0] + t * t * c[0],
+            u * u * a[1] + 2.0 * u * t * b[1] + t * t * c[1],
+        ]
+    }
+
+    /// the curve's length, near enough, given the size of a region in each axis
+    pub fn length(&self, scale: [f32; 2]) -> f32 {
+        let [a, b, c] = self.points;
+        let dist =
+            |p: [f32; 2], q: [f32; 2]| ((q[0] - p[0]) * scale[0]).hypot((q[1] - p[1]) * scale[1]);
+        // halfway between the chord and the control polygon
+        (dist(a, c) + dist(a, b) + dist(b, c)) / 2.0
+    }
+
+    /// the direction the curve mostly runs in, end to end
+    pub fn axis(&self) -> RoadAxis {
+        let [a, _, c] = self.points;
+        if (c[0] - a[0]).abs() >= (c[1] - a[1]).abs() {
+            RoadAxis::Horizontal
+        } else {
+            RoadAxis::Vertical
+        }
+    }
+
+    /// every region the curve's centerline passes through, in order. Each one
+    /// borders the last, so the regions make an unbroken path
+    pub fn regions(&self) -> Vec<Region> {
+        let steps = (self.length([1.0, 1.0]) * 8.0).ceil().max(1.0) as usize;
+        let mut regions: Vec<Region> = Vec::new();
+        for step in 0..=steps {
+            let [x, y] = self.at(step as f32 / steps as f32);
+            let region = Region::new(x.floor() as i32, y.floor() as i32);
+            if let Some(last) = regions.last().cloned() {
+                if last == region {
+                    continue;
+                }
+                if last.x != region.x && last.y != region.y {
+                    // cut corners the long way round
+                    regions.push(Region::new(region.x, last.y));
+                }
+            }
+            regions.push(region);
+        }
+        regions
+    }
+}
+
+const AXES: [RoadAxis; 2] = [RoadAxis::Horizontal, RoadAxis::Vertical];
+
+#[derive(Clone, Debug)]
+pub struct RoadNode {
+    pub region: Region,
+    /// indexes of the edges meeting here
+    pub edges: Vec<usize>,
+    /// the connected component the node belongs to
+    pub component: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct RoadEdge {
+    /// node at the west or north end
+    pub from: usize,
+    /// node at the east or south end
+    pub to: usize,
+    pub axis: RoadAxis,
+    /// the most lanes found along the edge
+    pub lanes: u8,
+    /// index of the road's name
+    name: usize,
+    /// every region along the edge in order, including both end nodes
+    pub regions: Vec<Region>,
+}
+
+impl RoadEdge {
+    /// length of the edge in regions
+    pub fn length(&self) -> usize {
+        self.regions.len() - 1
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct RoadGraph {
+    nodes: Vec<RoadNode>,
+    edges: Vec<RoadEdge>,
+    names: Vec<String>,
+    /// node index by region
+    node_at: HashMap<Region, usize>,
+    /// the edge running through a region along each axis
+    edges_at: HashMap<(Region, RoadAxis), usize>,
+    /// number of regions of road in each connected component
+    component_sizes: Vec<usize>,
+    /// how far it is from each region of the world to the closest road, the
+    /// short way round in a wrapping world
+    nearest: HashMap<Region, (i32, i32)>,
+    /// where the world starts, for wrapping regions back inside it
+    origin: (i32, i32),
+    size: i32,
+    wrap: bool,
+}
+
+impl RoadGraph {
+    /// builds the graph from the world's road map, naming every road
+    pub fn build(world: &WorldState) -> RoadGraph {
+        let mut graph = RoadGraph {
+            origin: (world.min_x(), world.min_y()),
+            size: world.size() as i32,
+            wrap: world.edge == WorldEdge::Wrap,
+            ..RoadGraph::default()
+        };
+        for x in world.min_x()..world.max_x() {
+            for y in world.min_y()..world.max_y() {
+                let region = Region::new(x, y);
+                if is_node(world, region) {
+                    graph.add_node(region);
+                }
+            }
+        }
+        let mut i = 0;
+        while i < graph.nodes.len() {
+            for axis in AXES.iter() {
+                graph.walk(world, i, *axis);
+            }
+            i += 1;
+        }
+        // in a wrapping world a road can loop all the way around without meeting
+        // anything, so give each of those a node of its own
+        for x in world.min_x()..world.max_x() {
+            for y in world.min_y()..world.max_y() {
+                let region = Region::new(x, y);
+                for axis in AXES.iter() {
+                    if axis.lanes(world, region) > 0
+                        && !graph.node_at.contains_key(&region)
+                        && !graph.edges_at.contains_key(&(region, *axis))
+                    {
+                        let node = graph.add_node(region);
+                        graph.walk(world, node, *axis);
+                    }
+                }
+            }
+        }
+        graph.find_components();
+        graph.find_nearest(world);
+        graph.name_roads(world);
+        graph
+    }
+
+    /// puts <region> back inside the world, if the world wraps
+    fn canonical(&self, region: Region) -> Region {
+        if self.wrap && self.size > 0 {
+            Region::new(
+                (region.x - self.origin.0).rem_euclid(self.size) + self.origin.0,
+                (region.y - self.origin.1).rem_euclid(self.size) + self.origin.1,
+            )
+        } else {
+            Region::new(region.x, region.y)
+        }
+    }
+
+    /// how far it is from <from> to <to> in each axis, the short way round in a
+    /// wrapping world
+    pub fn offset(&self, from: Region, to: Region) -> (i32, i32) {
+        let (from, to) = (self.canonical(from), self.canonical(to));
+        let wrapped = |d: i32| {
+            if self.wrap && self.size > 0 {
+                (d + self.size / 2).rem_euclid(self.size) - self.size / 2
+            } else {
+                d
+            }
+        };
+        (wrapped(to.x - from.x), wrapped(to.y - from.y))
+    }
+
+    fn add_node(&mut self, region: Region) -> usize {
+        let index = self.nodes.len();
+        self.nodes.push(RoadNode {
+            region,
+            edges: Vec::new(),
+            component: 0,
+        });
+        self.node_at.insert(region, index);
+        index
+    }
+
+    /// follows the road from <node> east or south until it reaches another node,
+    /// adding an edge between them
+    fn walk(&mut self, world: &WorldState, node: usize, axis: RoadAxis) {
+        let start = self.nodes[node].region;
+        if !axis.linked(world, start) {
+            return;
+        }
+        let mut regions = vec![start];
+        let mut lanes = axis.lanes(world, start);
+        let mut current = start;
+        // a road can't be longer than the world is wide
+        for _ in 0..=self.size {
+            current = self.canonical(axis.next(current));
+            regions.push(current);
+            lanes = lanes.max(axis.lanes(world, current));
+            if let Some(to) = self.node_at.get(&current).cloned() {
+                let index = self.edges.len();
+                for region in regions.iter() {
+                    self.edges_at.entry((*region, axis)).or_insert(index);
+                }
+                self.nodes[node].edges.push(index);
+                if to != node {
+                    self.nodes[to].edges.push(index);
+                }
+                self.edges.push(RoadEdge {
+                    from: node,
+                    to,
+                    axis,
+                    lanes,
+                    name: 0,
+                    regions,
+                });
+                return;
+            }
+        }
+        println!("road from {:?} never reached an intersection", start);
+    }
+
+    /// labels every node with its connected component, and counts the regions of
+    /// road in each
+    fn find_components(&mut self) {
+        let mut seen = vec![false; self.nodes.len()];
+        for start in 0..self.nodes.len() {
+            if seen[start] {
+                continue;
+            }
+            let component = self.component_sizes.len();
+            let mut size = 0;
+            let mut stack = vec![start];
+            seen[start] = true;
+            while let Some(node) = stack.pop() {
+                self.nodes[node].component = component;
+                size += 1;
+                for edge in self.nodes[node].edges.iter() {
+                    let edge = &self.edges[*edge];
+                    if edge.from == node {
+                        // count the regions between the nodes once, from the start
+                        size += edge.regions.len().saturating_sub(2);
+                    }
+                    for next in [edge.from, edge.to].iter() {
+                        if !seen[*next] {
+                            seen[*next] = true;
+                            stack.push(*next);
+                        }
+                    }
+                }
+            }
+            self.component_sizes.push(size);
+        }
+    }
+
+    /// spreads out from every region of road at once, so each region of the
+    /// world learns the way to the closest one
+    fn find_nearest(&mut self, world: &WorldState) {
+        let mut queue: VecDeque<Region> = VecDeque::new();
+        let roads: Vec<Region> = self
+            .nodes
+            .iter()
+            .map(|node| node.region)
+            .chain(
+                self.edges
+                    .iter()
+                    .flat_map(|edge| edge.regions.iter().cloned()),
+            )
+            .collect();
+        for region in roads {
+            let region = self.canonical(region);
+            if self.nearest.insert(region, (0, 0)).is_none() {
+                queue.push_back(region);
+            }
+        }
+        while let Some(region) = queue.pop_front() {
+            let (dx, dy) = self.nearest[&region];
+            for (sx, sy) in [(1, 0), (-1, 0), (0, 1), (0, -1)].iter() {
+                let next = Region::new(region.x + sx, region.y + sy);
+                if !self.wrap
+                    && (next.x < world.min_x()
+                        || next.x >= world.max_x()
+                        || next.y < world.min_y()
+                        || next.y >= world.max_y())
+                {
+                    continue;
+                }
+                let next = self.canonical(next);
+                if let Entry::Vacant(entry) = self.nearest.entry(next) {
+                    entry.insert((dx - sx, dy - sy));
+                    queue.push_back(next);
+                }
+            }
+        }
+    }
+
+    /// gives every road a name. A straight road keeps its name through each
+    /// intersection for as long as it stays the same class of road
+    fn name_roads(&mut self, world: &WorldState) {
+        let rng = &mut Pcg32::seed_from_u64(u64::from(world.seed()));
+        let mut used: HashSet<String> = world
+            .road_curves
+            .iter()
+            .map(|curve| curve.name.clone())
+            .collect();
+        // edges are found west to east and north to south, so the edge arriving
+        // at a node is always named before the one leaving it
+        let mut arriving: HashMap<(usize, RoadAxis), usize> = HashMap::new();
+        for index in 0..self.edges.len() {
+            let (from, to, axis, lanes) = {
+                let edge = &self.edges[index];
+                (edge.from, edge.to, edge.axis, edge.lanes)
+            };
+            let inherited = match arriving.get(&(from, axis)) {
+                Some(prev) if is_highway(self.edges[*prev].lanes) == is_highway(lanes) => {
+                    Some(self.edges[*prev].name)
+                }
+                _ => None,
+            };
+            let name = match inherited {
+                Some(name) => name,
+                None => {
+                    let name = road_name(rng, &used, axis, lanes);
+                    used.insert(name.clone());
+                    self.names.push(name);
+                    self.names.len() - 1
+                }
+            };
+            self.edges[index].name = name;
+            arriving.insert((to, axis), index);
+        }
+    }
+
+    pub fn nodes(&self) -> &Vec<RoadNode> {
+        &self.nodes
+    }
+
+    pub fn edges(&self) -> &Vec<RoadEdge> {
+        &self.edges
+    }
+
+    /// the name of a road edge
+    pub fn name(&self, edge: &RoadEdge) -> &str {
+        &self.names[edge.name]
+    }
+
+    /// the name of the road running through <region> along <axis>
+    pub fn name_at(&self, region: Region, axis: RoadAxis) -> Option<&str> {
+        self.edges_at
+            .get(&(self.canonical(region), axis))
+            .map(|edge| self.name(&self.edges[*edge]))
+    }
+
+    /// the names of every road running through <region>, east-west first
+    pub fn names_at(&self, region: Region) -> Vec<&str> {
+        let mut names: Vec<&str> = AXES
+            .iter()
+            .filter_map(|axis| self.name_at(region, *axis))
+            .collect();
+        names.dedup();
+        names
+    }
+
+    /// the connected component of the road network running through <region>
+    #[cfg(test)]
+    pub fn component_at(&self, region: Region) -> Option<usize> {
+        let region = self.canonical(region);
+        if let Some(node) = self.node_at.get(&region) {
+            return Some(self.nodes[*node].component);
+        }
+        AXES.iter()
+            .filter_map(|axis| self.edges_at.get(&(region, *axis)))
+            .map(|edge| self.nodes[self.edges[*edge].from].component)
+            .next()
+    }
+
+    /// every region of road belonging to components of fewer than <min_size>
+    /// regions
+    pub fn fragments(&self, min_size: usize) -> Vec<Region> {
+        let small = |component: usize| self.component_sizes[component] < min_size;
+        let mut regions: Vec<Region> = self
+            .nodes
+            .iter()
+            .filter(|node| small(node.component))
+            .map(|node| node.region)
+            .collect();
+        for edge in self.edges.iter() {
+            if small(self.nodes[edge.from].component) {
+                let inner = edge.regions.len().saturating_sub(1);
+                regions.extend(edge.regions.iter().take(inner).skip(1));
+            }
+        }
+        regions
+    }
+
+    /// the closest region with a road on it to <region>, if there are any roads.
+    /// It's found relative to <region>, so in a wrapping world it may lie past the
+    /// edge when the short way there is across it
+    pub fn nearest_road(&self, region: Region) -> Option<Region> {
+        self.nearest
+            .get(&self.canonical(region))
+            .map(|(dx, dy)| Region::new(region.x + dx, region.y + dy))
+    }
+
+    /// the nodes reachable directly from a region of road, along with the cost
+    /// and path of getting to each
+    fn anchors(&self, region: Region) -> Vec<(usize, usize, Vec<Region>)> {
+        if let Some(node) = self.node_at.get(&region) {
+            return vec![(*node, 0, vec![region])];
+        }
+        let mut anchors = Vec::new();
+        for axis in AXES.iter() {
+            if let Some(index) = self.edges_at.get(&(region, *axis)) {
+                let edge = &self.edges[*index];
+                if let Some(p) = edge.regions.iter().position(|r| *r == region) {
+                    let mut back: Vec<Region> = edge.regions[..=p].to_vec();
+                    back.reverse();
+                    anchors.push((edge.from, p, back));
+                    anchors.push((edge.to, edge.length() - p, edge.regions[p..].to_vec()));
+                }
+            }
+        }
+        anchors
+    }
+
+    /// finds the shortest way by road from <from> to <to>, which must both be on
+    /// roads in the same component. Gives every region along the way, including
+    /// the start and end
+    pub fn route(&self, from: Region, to: Region) -> Option<Vec<Region>> {
+        let from = self.canonical(from);
+        let to = self.canonical(to);
+        if from == to {
+            return Some(vec![from]);
+        }
+        let starts = self.anchors(from);
+        let ends = self.anchors(to);
+        if starts.is_empty() || ends.is_empty() {
+            return None;
+        }
+
+        let mut best: Option<(usize, Vec<Region>)> = None;
+        // both ends on the same stretch of road
+        for axis in AXES.iter() {
+            if let (Some(a), Some(b)) = (
+                self.edges_at.get(&(from, *axis)),
+                self.edges_at.get(&(to, *axis)),
+            ) {
+                let regions = &self.edges[*a].regions;
+                if a != b || self.node_at.contains_key(&from) || self.node_at.contains_key(&to) {
+                    continue;
+                }
+                let p1 = regions.iter().position(|r| *r == from)?;
+                let p2 = regions.iter().position(|r| *r == to)?;
+                let path: Vec<Region> = if p1 <= p2 {
+                    regions[p1..=p2].to_vec()
+                } else {
+                    regions[p2..=p1].iter().rev().cloned().collect()
+                };
+                best = Some((path.len() - 1, path));
+            }
+        }
+
+        // dijkstra over the nodes, starting from each node next to <from>
+        let mut dist = vec![usize::MAX; self.nodes.len()];
+        let mut prev: Vec<Option<(usize, usize)>> = vec![None; self.nodes.len()];
+        let mut heap = BinaryHeap::new();
+        for (node, cost, _) in starts.iter() {
+            if *cost < dist[*node] {
+                dist[*node] = *cost;
+                heap.push(Reverse((*cost, *node)));
+            }
+        }
+        while let Some(Reverse((cost, node))) = heap.pop() {
+            if cost > dist[node] {
+                continue;
+            }
+            for index in self.nodes[node].edges.iter() {
+                let edge = &self.edges[*index];
+                let next = if edge.from == node {
+                    edge.to
+                } else {
+                    edge.from
+                };
+                let next_cost = cost + edge.length();
+                if next != node && next_cost < dist[next] {
+                    dist[next] = next_cost;
+                    prev[next] = Some((node, *index));
+                    heap.push(Reverse((next_cost, next)));
+                }
+            }
+        }
+
+        for (end, end_cost, end_path) in ends.iter() {
+            if dist[*end] == usize::MAX {
+                continue;
+            }
+            let total = dist[*end] + end_cost;
+            let better = match &best {
+                Some((cost, _)) => total < *cost,
+                None => true,
+            };
+            if better {
+                best = Some((total, self.trace(&starts, &prev, *end, end_path)));
+            }
+        }
+        best.map(|(_, path)| path)
+    }
+
+    /// puts together the path found by route, ending at node <end>
+    fn trace(
+        &self,
+        starts: &[(usize, usize, Vec<Region>)],
+        prev: &[Option<(usize, usize)>],
+        end: usize,
+        end_path: &[Region],
+    ) -> Vec<Region> {
+        // walk back to the start, collecting each edge's regions on the way
+        let mut legs: Vec<Vec<Region>> = Vec::new();
+        let mut node = end;
+        while let Some((before, index)) = prev[node] {
+            let edge = &self.edges[index];
+            let mut leg = edge.regions.clone();
+            if edge.from != before {
+                leg.reverse();
+            }
+            legs.push(leg);
+            node = before;
+        }
+        let mut path = match starts.iter().find(|(start, _, _)| *start == node) {
+            Some((_, _, start_path)) => start_path.clone(),
+            None => vec![self.nodes[node].region],
+        };
+        for leg in legs.iter().rev() {
+            path.extend(leg.iter().skip(1));
+        }
+        // <end_path> runs from the destination to the node
+        path.extend(end_path.iter().rev().skip(1));
+        path
+    }
+}
+
+pub fn is_highway(lanes: u8) -> bool {
+    lanes >= HIGHWAY_LANES
+}
+
+/// whether <region> needs a node: every intersection and dead end, plus any
+/// lone region of road
+fn is_node(world: &WorldState, region: Region) -> bool {
+    let road = world.get_road(region);
+    if road.lanes_x == 0 && road.lanes_y == 0 {
+        return false;
+    }
+    if road.lanes_x > 0 && road.lanes_y > 0 {
+        return true;
+    }
+    let links = AXES
+        .iter()
+        .map(|axis| {
+            axis.linked(world, region) as usize + axis.linked(world, axis.prev(region)) as usize
+        })
+        .sum::<usize>();
+    links != 2
+}
+
+/// makes up a name for a road that hasn't been used yet
+pub fn road_name(rng: &mut Pcg32, used: &HashSet<String>, axis: RoadAxis, lanes: u8) -> String {
+    for _ in 0..NAME_TRIES {
+        let name = if is_highway(lanes) {
+            // like real highways, east-west routes get even numbers
+            let number = rng.gen_range(1, 500) * 2;
+            let number = match axis {
+                RoadAxis::Horizontal => number,
+                RoadAxis::Vertical => number - 1,
+            };
+            if lanes >= INTERSTATE_LANES {
+                format!("Interstate {}", number)
+            } else {
+                format!("Route {}", number)
+            }
+        } else {
+            let suffixes = match axis {
+                RoadAxis::Horizontal => &EAST_WEST_SUFFIXES,
+                RoadAxis::Vertical => &NORTH_SOUTH_SUFFIXES,
+            };
+            format!(
+                "{} {}",
+                STREET_NAMES.choose(rng).unwrap(),
+                suffixes.choose(rng).unwrap()
+            )
+        };
+        if !used.contains(&name) {
+            return name;
+        }
+    }
+    // run out of good names, so start numbering them
+    let suffix = match axis {
+        RoadAxis::Horizontal => "Street",
+        RoadAxis::Vertical => "Avenue",
+    };
+    (1..)
+        .map(|n| format!("{} {}", ordinal(n), suffix))
+        .find(|name| !used.contains(name))
+        .unwrap()
+}
+
+/// 1 -> 1st, 2 -> 2nd, 11 -> 11th and so on
+fn ordinal(n: usize) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11) | (_, 12) | (_, 13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{}{}", n, suffix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// a small world with a street across it, a highway crossing the street,
+    /// and a short stray fragment of road
+    fn test_world() -> WorldState {
+        let mut world = WorldState::new(8);
+        for x in -4..4 {
+            world.set_road(Region::new(x, -2), 1, 0);
+        }
+        for y in -4..4 {
+            world.set_road(Region::new(0, y), 0, 4);
+        }
+        world.set_road(Region::new(0, -2), 1, 4);
+        world.set_road(Region::new(-3, 2), 1, 0);
+        world.set_road(Region::new(-2, 2), 1, 0);
+        world
+    }
+
+    #[test]
+    fn road_graph() {
+        let world = test_world();
+        let graph = RoadGraph::build(&world);
+        // the intersection, four dead ends of the cross, and two for the fragment
+        assert_eq!(graph.nodes().len(), 7);
+        assert_eq!(graph.edges().len(), 5);
+
+        let street = graph
+            .name_at(Region::new(-3, -2), RoadAxis::Horizontal)
+            .unwrap();
+        let highway = graph
+            .name_at(Region::new(0, 3), RoadAxis::Vertical)
+            .unwrap();
+        assert_eq!(
+            graph.name_at(Region::new(3, -2), RoadAxis::Horizontal),
+            Some(street)
+        );
+        assert_eq!(
+            graph.name_at(Region::new(0, -4), RoadAxis::Vertical),
+            Some(highway)
+        );
+        assert!(highway.starts_with("Route"));
+        assert_ne!(street, highway);
+        assert_eq!(graph.names_at(Region::new(0, -2)).len(), 2);
+
+        assert_ne!(
+            graph.component_at(Region::new(-3, 2)),
+            graph.component_at(Region::new(3, -2))
+        );
+        let fragments = graph.fragments(3);
+        assert_eq!(fragments.len(), 2);
+        assert!(fragments.contains(&Region::new(-3, 2)));
+
+        assert_eq!(
+            graph.nearest_road(Region::new(2, 3)),
+            Some(Region::new(0, 3))
+        );
+    }
+
+    #[test]
+    fn nearest_road_wraps() {
+        let mut world = WorldState::new(8);
+        world.edge = WorldEdge::Wrap;
+        for y in -4..4 {
+            world.set_road(Region::new(-4, y), 0, 1);
+        }
+        let graph = RoadGraph::build(&world);
+        // one region east across the edge, rather than seven west
+        assert_eq!(
+            graph.nearest_road(Region::new(3, 1)),
+            Some(Region::new(4, 1))
+        );
+        assert_eq!(
+            graph.nearest_road(Region::new(-3, 1)),
+            Some(Region::new(-4, 1))
+        );
+    }
+
+    #[test]
+    fn road_curve() {
+        let curve = RoadCurve {
+            points: [[0.5, 0.5], [4.0, 0.5], [4.5, 4.5]],
+            lanes: 4,
+            name: "Route 1".to_string(),
+        };
+        let regions = curve.regions();
+        assert_eq!(regions.first(), Some(&Region::new(0, 0)));
+        assert_eq!(regions.last(), Some(&Region::new(4, 4)));
+        for pair in regions.windows(2) {
+            let step = (pair[1].x - pair[0].x).abs() + (pair[1].y - pair[0].y).abs();
+            assert_eq!(step, 1, "curve skipped from {:?} to {:?}", pair[0], pair[1]);
+        }
+    }
+
+    #[test]
+    fn road_route() {
+        let world = test_world();
+        let graph = RoadGraph::build(&world);
+        let route = graph.route(Region::new(-3, -2), Region::new(0, 2)).unwrap();
+        assert_eq!(route.first(), Some(&Region::new(-3, -2)));
+        assert_eq!(route.last(), Some(&Region::new(0, 2)));
+        // three along the street, then four down the highway
+        assert_eq!(route.len(), 8);
+        assert!(route.contains(&Region::new(0, -2)));
+
+        // along a single stretch of road
+        let route = graph.route(Region::new(0, 3), Region::new(0, 1)).unwrap();
+        assert_eq!(
+            route,
+            vec![Region::new(0, 3), Region::new(0, 2), Region::new(0, 1)]
+        );
+
+        // no way across to the fragment
+        assert!(graph
+            .route(Region::new(-3, -2), Region::new(-2, 2))
+            .is_none());
+    }
+}
@@ -0,0 +1,190 @@
+use crate::component::{MovePlan, Pos, Region};
+use crate::resource::{CollisionMaps, RegionMap, RoadGraph};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// where the player is headed when moving on their own
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TravelGoal {
+    /// toward the nearest tile the player hasn't seen yet
+    Explore,
+    /// to a region, and a tile within it if one was picked
+    To { region: Region, pos: Option<Pos> },
+}
+
+/// the player's current auto-explore or travel order, if any
+#[derive(Default)]
+pub struct Travel {
+    pub goal: Option<TravelGoal>,
+    /// regions still to pass through on the way to the goal, by road where
+    /// possible. Worked out on the first step
+    route: Option<Vec<Region>>,
+    /// regions explored as far as they can be, which exploring won't head back to
+    exhausted: HashSet<Region>,
+}
+
+impl Travel {
+    pub fn start(&mut self, goal: TravelGoal) {
+        self.goal = Some(goal);
+        self.route = None;
+    }
+
+    pub fn stop(&mut self) {
+        self.goal = None;
+        self.route = None;
+    }
+
+    /// marks <region> as having nothing left to explore
+    pub fn exhaust(&mut self, region: Region) {
+        self.exhausted.insert(region);
+    }
+
+    pub fn exhausted(&self, region: Region) -> bool {
+        self.exhausted.contains(&region)
+    }
+
+    /// the next region to head for from <current>, if the goal is in another
+    /// region. Follows the roads while they lead on from here, and heads
+    /// straight for the goal otherwise, across the edge of a wrapping world when
+    /// that's the short way
+    pub fn next_region(&mut self, roads: &RoadGraph, current: Region) -> Option<Region> {
+        let to = match self.goal {
+            Some(TravelGoal::To { region, .. }) if roads.offset(current, region) != (0, 0) => {
+                region
+            }
+            _ => return None,
+        };
+        let step = |(dx, dy): (i32, i32)| Region::new(current.x + dx, current.y + dy);
+        let route = self
+            .route
+            .get_or_insert_with(|| roads.route(current, to).unwrap_or_default());
+        while route.first().map(|r| roads.offset(current, *r)) == Some((0, 0)) {
+            route.remove(0);
+        }
+        if let Some(next) = route.first() {
+            let (dx, dy) = roads.offset(current, *next);
+            if dx.abs() + dy.abs() == 1 {
+                return Some(step((dx, dy)));
+            }
+            // wandered off the road
+            route.clear();
+        }
+        // one axis at a time, since regions only border along their edges
+        let (dx, dy) = roads.offset(current, to);
+        if dx.abs() >= dy.abs() {
+            Some(step((dx.signum(), 0)))
+        } else {
+            Some(step((0, dy.signum())))
+        }
+    }
+}
+
+/// the first step on the shortest walk from <from> to any tile that meets <goal>,
+/// going around walls and anything solid. None if there's no way there, or the
+/// walker is already at a goal
+pub fn path_step(
+    map: &RegionMap,
+    collisions: &CollisionMaps,
+    region: Region,
+    from: Pos,
+    goal: impl Fn(Pos) -> bool,
+) -> Option<MovePlan> {
+    let mut came_from: HashMap<Pos, Pos> = HashMap::new();
+    let mut queue: VecDeque<Pos> = VecDeque::new();
+    came_from.insert(from, from);
+    queue.push_back(from);
+    while let Some(pos) = queue.pop_front() {
+        if goal(pos) {
+            if pos == from {
+                return None;
+            }
+            // walk back to the tile next to the start
+            let mut step = pos;
+            while came_from[&step] != from {
+                step = came_from[&step];
+            }
+            return Some(MovePlan {
+                x: step.x as i32 - from.x as i32,
+                y: step.y as i32 - from.y as i32,
+            });
+        }
+        for (dx, dy) in NEIGHBORS.iter() {
+            let (x, y) = (pos.x as i32 + dx, pos.y as i32 + dy);
+            if x < 0 || y < 0 {
+                continue;
+            }
+            let next = Pos::new(x as usize, y as usize);
+            if came_from.contains_key(&next) {
+                continue;
+            }
+            let open = map.get(next).map(|t| t.walkable) == Some(true);
+            if !open || collisions.get(region, next) {
+                continue;
+            }
+            came_from.insert(next, pos);
+            queue.push_back(next);
+        }
+    }
+    None
+}
+
+/// straight steps come first so paths don't zigzag more than they need to
+const NEIGHBORS: [(i32, i32); 8] = [
+    (0, -1),
+    (1, 0),
+    (0, 1),
+    (-1, 0),
+    (-1, -1),
+    (1, -1),
+    (1, 1),
+    (-1, 1),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resource::{WorldEdge, WorldState};
+
+    #[test]
+    fn path_goes_around_walls() {
+        let mut map = RegionMap::default();
+        // a wall across the top with a gap at x = 8
+        for x in 0..8 {
+            map.get_mut(Pos::new(x, 2)).unwrap().walkable = false;
+        }
+        let region = Region::new(0, 0);
+        let mut collisions = CollisionMaps::default();
+        collisions.init_level(region);
+        let goal = Pos::new(0, 0);
+        let mut pos = Pos::new(0, 4);
+        let mut steps = 0;
+        while let Some(step) = path_step(&map, &collisions, region, pos, |p| p == goal) {
+            pos = Pos::new(
+                (pos.x as i32 + step.x) as usize,
+                (pos.y as i32 + step.y) as usize,
+            );
+            assert!(map.get(pos).unwrap().walkable);
+            steps += 1;
+        }
+        assert_eq!(pos, goal);
+        assert_eq!(steps, 16);
+    }
+
+    #[test]
+    fn travel_wraps_around_the_world() {
+        let mut world = WorldState::new(8);
+        world.edge = WorldEdge::Wrap;
+        let roads = RoadGraph::build(&world);
+        let mut travel = Travel::default();
+        travel.start(TravelGoal::To {
+            region: Region::new(-4, 0),
+            pos: None,
+        });
+        // east over the edge is one region, where west is seven
+        assert_eq!(
+            travel.next_region(&roads, Region::new(3, 0)),
+            Some(Region::new(4, 0))
+        );
+        // and the goal counts as reached from the far side
+        assert_eq!(travel.next_region(&roads, Region::new(4, 0)), None);
+    }
+}
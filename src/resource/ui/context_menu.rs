@@ -0,0 +1,96 @@
+use super::Widget;
+use super::{UIAction, UIElementType, UIResponse};
+use crate::component::Pos;
+use crate::resource::InteractionMethod;
+use tcod::input::{Key, KeyCode::*};
+
+/// a list of the verbs available for whatever is at <pos>, to pick one from
+pub struct ContextMenu {
+    title: String,
+    pos: Pos,
+    verbs: Vec<InteractionMethod>,
+    actions: Vec<UIAction>,
+}
+
+impl ContextMenu {
+    pub fn new(title: String, pos: Pos, verbs: Vec<InteractionMethod>) -> ContextMenu {
+        ContextMenu {
+            title,
+            pos,
+            verbs,
+            actions: Vec::new(),
+        }
+    }
+}
+
+impl Widget for ContextMenu {
+    fn get_type(&self) -> UIElementType {
+        UIElementType::ContextMenu
+    }
+    fn get_title(&self) -> String {
+        self.title.clone()
+    }
+    fn get_body(&self) -> String {
+        self.verbs
+            .iter()
+            .enumerate()
+            .map(|(i, verb)| format!("{}) {}", i + 1, verb.name()))
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+    fn get_footer(&self) -> String {
+        "[1-9] Choose [Esc] Cancel".to_string()
+    }
+    fn next(&mut self, input: Key) -> UIResponse {
+        match input {
+            Key { code: Escape, .. } => UIResponse::Completed,
+            Key { printable, .. } if ('1'..='9').contains(&printable) => {
+                let index = printable as usize - '1' as usize;
+                match self.verbs.get(index) {
+                    Some(verb) => {
+                        self.actions.push(UIAction::Interact(*verb, self.pos));
+                        UIResponse::Completed
+                    }
+                    None => UIResponse::Unrecognized,
+                }
+            }
+            _ => UIResponse::Unrecognized,
+        }
+    }
+    fn done(&self) -> bool {
+        false
+    }
+    fn take_actions(&mut self) -> Vec<UIAction> {
+        std::mem::take(&mut self.actions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn context_menu_choice() {
+        let pos = Pos::new(3, 4);
+        let verbs = vec![InteractionMethod::Examine, InteractionMethod::Search];
+        let mut menu = ContextMenu::new("rubble".to_string(), pos, verbs);
+        let key = |printable| {
+            let mut key = Key::default();
+            key.printable = printable;
+            key
+        };
+        assert!(menu.get_body().contains("2) Search"));
+        match menu.next(key('3')) {
+            UIResponse::Unrecognized => {}
+            _ => panic!("chose a verb that isn't on offer"),
+        }
+        match menu.next(key('2')) {
+            UIResponse::Completed => {}
+            _ => panic!("choosing a verb should close the menu"),
+        }
+        match menu.take_actions().as_slice() {
+            [UIAction::Interact(InteractionMethod::Search, chosen)] => assert_eq!(*chosen, pos),
+            _ => panic!("choosing a verb should take an action"),
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use super::Widget;
+use super::{UIAction, UIElementType, UIResponse};
+use crate::resource::{DialogueContext, DialogueTemplate};
+use tcod::input::{Key, KeyCode::*};
+
+/// a conversation with an NPC, walking through a dialogue template one node at
+/// a time
+pub struct DialogueWidget {
+    speaker: String,
+    template: DialogueTemplate,
+    node: String,
+    context: DialogueContext,
+    actions: Vec<UIAction>,
+    done: bool,
+}
+
+impl DialogueWidget {
+    pub fn new(
+        speaker: String,
+        template: DialogueTemplate,
+        context: DialogueContext,
+    ) -> DialogueWidget {
+        DialogueWidget {
+            speaker,
+            node: template.start.clone(),
+            template,
+            context,
+            actions: Vec::new(),
+            done: false,
+        }
+    }
+
+    /// chooses the <index>th option on offer, returning false if there isn't one
+    fn choose(&mut self, index: usize) -> bool {
+        let option = match self.template.options(&self.node, &self.context).get(index) {
+            Some(option) => (*option).clone(),
+            None => return false,
+        };
+        for effect in &option.effects {
+            self.context.apply(effect);
+            self.actions.push(UIAction::Dialogue(effect.clone()));
+        }
+        match option.next {
+            Some(next) => self.node = next,
+            None => self.done = true,
+        }
+        true
+    }
+}
+
+impl Widget for DialogueWidget {
+    fn get_type(&self) -> UIElementType {
+        UIElementType::Dialogue
+    }
+    fn get_title(&self) -> String {
+        self.speaker.clone()
+    }
+    fn get_body(&self) -> String {
+        let mut body = match self.template.nodes.get(&self.node) {
+            Some(node) => node.text.clone(),
+            None => "...".to_string(),
+        };
+        for (i, option) in self
+            .template
+            .options(&self.node, &self.context)
+            .iter()
+            .enumerate()
+        {
+            body.push_str(&format!("\n\n{}) {}", i + 1, option.text));
+        }
+        body
+    }
+    fn get_footer(&self) -> String {
+        "[1-9] Choose [Esc] Leave".to_string()
+    }
+    fn next(&mut self, input: Key) -> UIResponse {
+        match input {
+            Key { code: Escape, .. } => UIResponse::Completed,
+            Key { printable, .. } if ('1'..='9').contains(&printable) => {
+                let index = printable as usize - '1' as usize;
+                if !self.choose(index) {
+                    UIResponse::Unrecognized
+                } else if self.done {
+                    UIResponse::Completed
+                } else {
+                    UIResponse::Consumed
+                }
+            }
+            _ => UIResponse::Unrecognized,
+        }
+    }
+    fn done(&self) -> bool {
+        self.done
+    }
+    fn take_actions(&mut self) -> Vec<UIAction> {
+        std::mem::take(&mut self.actions)
+    }
+}
@@ -1,20 +1,44 @@
 use specs::{Component, VecStorage};
 use std::sync::{Arc, Mutex};
+use crate::component::{Pos, Region};
+use crate::resource::{DialogueEffect, InteractionMethod};
 use tcod::input::Key; //, KeyCode::*};
 
+mod context_menu;
+mod dialogue;
 mod notification;
+mod quest_log;
 mod types;
+mod world_map;
+pub use context_menu::*;
+pub use dialogue::*;
 pub use notification::*;
+pub use quest_log::*;
+pub use world_map::*;
 use types::*;
 
 pub enum UIResponse {
-    /* Consumed, FIXME UNUSED */
+    /// the input was used, but the widget isn't done yet
+    Consumed,
     Completed,
     Unrecognized,
 }
 
+/// something a widget wants done to the game, applied by a system after the
+/// widget hands it over
+#[derive(Clone, Debug)]
+pub enum UIAction {
+    Dialogue(DialogueEffect),
+    /// use a verb on whatever is at the position
+    Interact(InteractionMethod, Pos),
+    /// travel to the region
+    Travel(Region),
+}
+
 pub struct UIQueue {
     stack: Vec<Arc<Mutex<dyn Widget>>>,
+    /// actions taken by widgets, waiting to be applied
+    actions: Vec<UIAction>,
 }
 
 impl Component for UIQueue {
@@ -23,7 +47,10 @@ impl Component for UIQueue {
 
 impl Default for UIQueue {
     fn default() -> UIQueue {
-        UIQueue { stack: Vec::new() }
+        UIQueue {
+            stack: Vec::new(),
+            actions: Vec::new(),
+        }
     }
 }
 
@@ -51,12 +78,18 @@ impl UIQueue {
         {
             let top = &mut self.stack[0].lock().unwrap();
             response = top.next(input);
+            self.actions.append(&mut top.take_actions());
         }
         if let UIResponse::Completed = response {
             self.stack.remove(0);
         }
         response
     }
+
+    /// hands over the actions widgets have taken since the last call
+    pub fn take_actions(&mut self) -> Vec<UIAction> {
+        std::mem::take(&mut self.actions)
+    }
 }
 
 pub trait Widget: Send + Sync {
@@ -73,4 +106,12 @@ pub trait Widget: Send + Sync {
     /// Passes user input to the widget for consumption and returns true if it still has
     /// stuff to do
     fn next(&mut self, input: Key) -> UIResponse;
+    /// hands over any actions the widget has taken, for systems to apply
+    fn take_actions(&mut self) -> Vec<UIAction> {
+        Vec::new()
+    }
+    /// the part of the world to draw, for widgets that show the world map
+    fn map_view(&self) -> Option<MapView> {
+        None
+    }
 }
@@ -0,0 +1,76 @@
+use super::Widget;
+use super::{UIElementType, UIResponse};
+use crate::resource::{Assets, QuestLog};
+use tcod::input::{Key, KeyCode::*};
+
+/// lists the player's quests, with the objectives of the current stage of each
+pub struct QuestLogWidget {
+    body: String,
+}
+
+impl QuestLogWidget {
+    pub fn new(log: &QuestLog, assets: &Assets) -> QuestLogWidget {
+        let mut active = String::new();
+        let mut finished = String::new();
+        for progress in log.quests() {
+            let template = match assets.get_quest(&progress.quest) {
+                Some(template) => template,
+                None => continue,
+            };
+            let stage = match template.stages.get(progress.stage) {
+                Some(stage) => stage,
+                None => {
+                    finished.push_str(&format!("\n{}", template.title));
+                    continue;
+                }
+            };
+            active.push_str(&format!("{}\n{}\n", template.title, stage.description));
+            for (i, objective) in stage.objectives.iter().enumerate() {
+                let count = progress.counts.get(i).copied().unwrap_or(0);
+                let mark = if count >= objective.needed() {
+                    'x'
+                } else {
+                    ' '
+                };
+                active.push_str(&format!("[{}] {}", mark, objective.describe(assets)));
+                if objective.needed() > 1 {
+                    active.push_str(&format!(" ({}/{})", count, objective.needed()));
+                }
+                active.push('\n');
+            }
+            active.push('\n');
+        }
+        if active.is_empty() {
+            active = "You haven't taken on any quests.\n".to_string();
+        }
+        if !finished.is_empty() {
+            active.push_str(&format!("\nFinished:{}", finished));
+        }
+        QuestLogWidget { body: active }
+    }
+}
+
+impl Widget for QuestLogWidget {
+    fn get_type(&self) -> UIElementType {
+        UIElementType::QuestLog
+    }
+    fn get_title(&self) -> String {
+        "Quests".to_string()
+    }
+    fn get_body(&self) -> String {
+        self.body.clone()
+    }
+    fn get_footer(&self) -> String {
+        "[Esc] Close".to_string()
+    }
+    fn next(&mut self, input: Key) -> UIResponse {
+        match input {
+            Key { code: Escape, .. } => UIResponse::Completed,
+            Key { printable: 'j', .. } => UIResponse::Completed,
+            _ => UIResponse::Unrecognized,
+        }
+    }
+    fn done(&self) -> bool {
+        false
+    }
+}
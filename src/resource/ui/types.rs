@@ -1,3 +1,7 @@
 pub enum UIElementType {
     Notification,
+    Dialogue,
+    ContextMenu,
+    QuestLog,
+    WorldMap,
 }
@@ -0,0 +1,192 @@
+use super::Widget;
+use super::{UIAction, UIElementType, UIResponse};
+use crate::component::Region;
+use crate::constants::{MAP_HEIGHT, MAP_WIDTH};
+use tcod::input::{Key, KeyCode::*};
+
+/// regions shown per map cell, from most to least detailed
+const ZOOM_LEVELS: [i32; 4] = [1, 2, 4, 8];
+/// cells visible either side of the view's center, inside the frame
+const HALF_WIDTH: i32 = MAP_WIDTH as i32 / 2 - 1;
+/// the bottom rows are left for the region info
+const HALF_HEIGHT: i32 = MAP_HEIGHT as i32 / 2 - 3;
+
+/// what part of the world the map widget is showing
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MapView {
+    /// the region in the middle of the view
+    pub center: Region,
+    /// the region picked by the cursor
+    pub cursor: Region,
+    /// regions per cell along each side
+    pub zoom: i32,
+    pub half_width: i32,
+    pub half_height: i32,
+}
+
+impl MapView {
+    /// the first region covered by the cell <x>, <y> cells from the center
+    pub fn region_at(&self, x: i32, y: i32) -> Region {
+        Region::new(self.center.x + x * self.zoom, self.center.y + y * self.zoom)
+    }
+
+    /// the cell covering <region>, in cells from the center
+    pub fn cell_of(&self, region: Region) -> (i32, i32) {
+        (
+            (region.x - self.center.x).div_euclid(self.zoom),
+            (region.y - self.center.y).div_euclid(self.zoom),
+        )
+    }
+}
+
+/// a full-screen map of the world, with a cursor to look at regions and pick
+/// somewhere to travel to
+pub struct WorldMapWidget {
+    view: MapView,
+    zoom_level: usize,
+    actions: Vec<UIAction>,
+}
+
+impl WorldMapWidget {
+    pub fn new(region: Region) -> WorldMapWidget {
+        let region = region.on_level(0);
+        WorldMapWidget {
+            view: MapView {
+                center: region,
+                cursor: region,
+                zoom: ZOOM_LEVELS[0],
+                half_width: HALF_WIDTH,
+                half_height: HALF_HEIGHT,
+            },
+            zoom_level: 0,
+            actions: Vec::new(),
+        }
+    }
+
+    /// moves the cursor by <dx>, <dy> cells, panning along when it leaves the view.
+    /// Panning moves the view and the cursor together
+    fn move_cursor(&mut self, dx: i32, dy: i32, pan: bool) {
+        let zoom = self.view.zoom;
+        self.view.cursor.x += dx * zoom;
+        self.view.cursor.y += dy * zoom;
+        if pan {
+            self.view.center.x += dx * zoom;
+            self.view.center.y += dy * zoom;
+        }
+        let (x, y) = self.view.cell_of(self.view.cursor);
+        if x.abs() > self.view.half_width {
+            self.view.center.x += (x - x.signum() * self.view.half_width) * zoom;
+        }
+        if y.abs() > self.view.half_height {
+            self.view.center.y += (y - y.signum() * self.view.half_height) * zoom;
+        }
+    }
+
+    fn zoom(&mut self, level: usize) {
+        self.zoom_level = level.min(ZOOM_LEVELS.len() - 1);
+        self.view.zoom = ZOOM_LEVELS[self.zoom_level];
+        // keep the cursor in view
+        self.move_cursor(0, 0, false);
+    }
+}
+
+impl Widget for WorldMapWidget {
+    fn get_type(&self) -> UIElementType {
+        UIElementType::WorldMap
+    }
+    fn get_title(&self) -> String {
+        "World Map".to_string()
+    }
+    fn get_body(&self) -> String {
+        String::new()
+    }
+    fn get_footer(&self) -> String {
+        "[+/-] Zoom [Shift] Pan [M]ode [t] Travel [Esc] Close".to_string()
+    }
+    fn next(&mut self, input: Key) -> UIResponse {
+        let pan = input.shift;
+        let step = match input.code {
+            NumPad7 => Some((-1, -1)),
+            NumPad8 => Some((0, -1)),
+            NumPad9 => Some((1, -1)),
+            NumPad4 => Some((-1, 0)),
+            NumPad6 => Some((1, 0)),
+            NumPad1 => Some((-1, 1)),
+            NumPad2 => Some((0, 1)),
+            NumPad3 => Some((1, 1)),
+            _ => None,
+        };
+        if let Some((dx, dy)) = step {
+            self.move_cursor(dx, dy, pan);
+            return UIResponse::Consumed;
+        }
+        match input {
+            Key { code: Escape, .. } | Key { printable: 'w', .. } => UIResponse::Completed,
+            Key {
+                code: NumPadAdd, ..
+            }
+            | Key { printable: '+', .. }
+            | Key { printable: '=', .. } => {
+                self.zoom(self.zoom_level.saturating_sub(1));
+                UIResponse::Consumed
+            }
+            Key {
+                code: NumPadSubtract,
+                ..
+            }
+            | Key { printable: '-', .. } => {
+                self.zoom(self.zoom_level + 1);
+                UIResponse::Consumed
+            }
+            Key { printable: 't', .. } => {
+                self.actions.push(UIAction::Travel(self.view.cursor));
+                UIResponse::Completed
+            }
+            _ => UIResponse::Unrecognized,
+        }
+    }
+    fn done(&self) -> bool {
+        false
+    }
+    fn take_actions(&mut self) -> Vec<UIAction> {
+        std::mem::take(&mut self.actions)
+    }
+    fn map_view(&self) -> Option<MapView> {
+        Some(self.view)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cursor_stays_in_view() {
+        let mut map = WorldMapWidget::new(Region::new(10, 10));
+        let key = |code| {
+            let mut key = Key::default();
+            key.code = code;
+            key
+        };
+        for _ in 0..HALF_WIDTH + 5 {
+            map.next(key(NumPad6));
+        }
+        let view = map.map_view().unwrap();
+        assert_eq!(view.cursor, Region::new(10 + HALF_WIDTH + 5, 10));
+        assert_eq!(view.cell_of(view.cursor), (HALF_WIDTH, 0));
+
+        // zooming out keeps the cursor's region under it
+        map.next(key(NumPadSubtract));
+        let view = map.map_view().unwrap();
+        assert_eq!(view.zoom, 2);
+        let (x, y) = view.cell_of(view.cursor);
+        let first = view.region_at(x, y);
+        assert!(view.cursor.x - first.x < 2 && view.cursor.y - first.y < 2);
+
+        let mut travel = key(Char);
+        travel.printable = 't';
+        map.next(travel);
+        // picking a region to travel to hands it over
+        assert_eq!(map.take_actions().len(), 1);
+    }
+}
@@ -0,0 +1,239 @@
+use crate::component::Region;
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// supplies a settlement gathers in a day, before anything it's good for
+const BASE_PRODUCTION: f32 = 0.03;
+/// extra supplies gathered in a day in the wettest regions
+const MOISTURE_PRODUCTION: f32 = 0.05;
+/// supplies each resident eats in a day
+const UPKEEP: f32 = 0.01;
+/// settlements this well stocked take in a new resident
+const GROWTH_SUPPLIES: f32 = 0.6;
+/// supplies it costs to take in a new resident
+const GROWTH_COST: f32 = 0.1;
+
+/// residents of one entity template at a settlement
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Residents {
+    pub template: String,
+    pub count: u32,
+    /// most that can live here
+    pub capacity: u32,
+}
+
+/// a point of interest as the world simulation sees it: who lives there, and
+/// how well they're getting by
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Settlement {
+    pub region: Region,
+    pub residents: Vec<Residents>,
+    /// from 0.0 (starving) to 1.0 (stocked up)
+    pub supplies: f32,
+    /// supplies gathered per day
+    production: f32,
+    /// whether the region is loaded, so its residents are out walking around
+    /// instead of being simulated
+    #[serde(skip)]
+    pub loaded: bool,
+}
+
+impl Settlement {
+    /// a settlement in <region>, producing more in wetter places
+    pub fn new(region: Region, moisture: f32, supplies: f32) -> Settlement {
+        Settlement {
+            region,
+            residents: Vec::new(),
+            supplies,
+            production: BASE_PRODUCTION + MOISTURE_PRODUCTION * moisture,
+            loaded: false,
+        }
+    }
+
+    /// everyone living here
+    pub fn population(&self) -> u32 {
+        self.residents.iter().map(|r| r.count).sum()
+    }
+
+    /// how many residents of <template> live here, or None if it doesn't
+    pub fn count(&self, template: &str) -> Option<u32> {
+        self.residents
+            .iter()
+            .find(|r| r.template == template)
+            .map(|r| r.count)
+    }
+
+    fn has_room_for(&self, template: &str) -> bool {
+        self.residents
+            .iter()
+            .any(|r| r.template == template && r.count < r.capacity)
+    }
+}
+
+/// residents who left a settlement for another, crossing the world a region a day
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Migration {
+    pub template: String,
+    /// where they are now
+    pub region: Region,
+    pub to: Region,
+}
+
+/// a coarse simulation of the world outside the loaded regions, a step per day.
+/// Settlements gather and eat supplies, grow when they're doing well, and send
+/// residents off to other settlements when they're not
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct WorldSim {
+    /// the day last simulated
+    day: u32,
+    pub settlements: Vec<Settlement>,
+    pub migrations: Vec<Migration>,
+}
+
+impl WorldSim {
+    /// the settlement in <region>, which needs to be the canonical region
+    pub fn settlement(&self, region: Region) -> Option<&Settlement> {
+        self.settlements.iter().find(|s| s.region == region)
+    }
+
+    pub fn settlement_mut(&mut self, region: Region) -> Option<&mut Settlement> {
+        self.settlements.iter_mut().find(|s| s.region == region)
+    }
+
+    /// simulates a day whenever <day> comes around. Returns whether it did
+    pub fn advance(&mut self, day: u32, rng: &mut impl Rng) -> bool {
+        if day == self.day {
+            return false;
+        }
+        self.day = day;
+        self.step(rng);
+        true
+    }
+
+    /// a day in the life of the wasteland, for everything that isn't loaded
+    fn step(&mut self, rng: &mut impl Rng) {
+        let mut leaving: Vec<(Region, String)> = Vec::new();
+        for settlement in self.settlements.iter_mut().filter(|s| !s.loaded) {
+            let upkeep = settlement.population() as f32 * UPKEEP;
+            settlement.supplies =
+                (settlement.supplies + settlement.production - upkeep).clamp(0.0, 1.0);
+            if settlement.supplies >= GROWTH_SUPPLIES {
+                if let Some(residents) = settlement
+                    .residents
+                    .iter_mut()
+                    .filter(|r| r.count < r.capacity)
+                    .choose(rng)
+                {
+                    residents.count += 1;
+                    settlement.supplies -= GROWTH_COST;
+                }
+            } else if settlement.supplies <= 0.0 {
+                // hungry mouths move on
+                if let Some(residents) = settlement
+                    .residents
+                    .iter_mut()
+                    .filter(|r| r.count > 0)
+                    .choose(rng)
+                {
+                    residents.count -= 1;
+                    leaving.push((settlement.region, residents.template.clone()));
+                }
+            }
+        }
+        for (from, template) in leaving {
+            // the closest place with room for them. Nowhere to go means they don't make it
+            let to = self
+                .settlements
+                .iter()
+                .filter(|s| s.region != from && s.has_room_for(&template))
+                .min_by_key(|s| distance(from, s.region))
+                .map(|s| s.region);
+            if let Some(to) = to {
+                self.migrations.push(Migration {
+                    template,
+                    region: from,
+                    to,
+                });
+            }
+        }
+
+        let mut arrived: Vec<usize> = Vec::new();
+        for (i, migration) in self.migrations.iter_mut().enumerate() {
+            if migration.region == migration.to {
+                arrived.push(i);
+                continue;
+            }
+            // regions only border along their edges, so one axis at a time
+            let (dx, dy) = (
+                migration.to.x - migration.region.x,
+                migration.to.y - migration.region.y,
+            );
+            if dx.abs() >= dy.abs() {
+                migration.region.x += dx.signum();
+            } else {
+                migration.region.y += dy.signum();
+            }
+        }
+        for i in arrived.into_iter().rev() {
+            let migration = self.migrations.remove(i);
+            match self.settlement_mut(migration.to) {
+                // loaded settlements take them in once they turn up in person
+                Some(settlement) if settlement.loaded => self.migrations.push(migration),
+                Some(settlement) => {
+                    if let Some(residents) = settlement
+                        .residents
+                        .iter_mut()
+                        .find(|r| r.template == migration.template)
+                    {
+                        residents.count += 1;
+                    }
+                }
+                None => {}
+            }
+        }
+    }
+}
+
+fn distance(a: Region, b: Region) -> i32 {
+    (a.x - b.x).abs() + (a.y - b.y).abs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_pcg::Pcg32;
+
+    #[test]
+    fn starving_settlements_send_residents_away() {
+        let mut rng = Pcg32::seed_from_u64(1);
+        let mut sim = WorldSim::default();
+        let mut hungry = Settlement::new(Region::new(0, 0), 0.0, 0.0);
+        hungry.residents.push(Residents {
+            template: "shockroach".to_string(),
+            count: 20,
+            capacity: 20,
+        });
+        // getting by, but not well enough to grow on its own
+        let mut home = Settlement::new(Region::new(2, 1), 0.0, 0.0);
+        home.residents.push(Residents {
+            template: "shockroach".to_string(),
+            count: 0,
+            capacity: 5,
+        });
+        sim.settlements = vec![hungry, home];
+
+        // the same day doesn't run twice
+        assert!(sim.advance(1, &mut rng));
+        assert!(!sim.advance(1, &mut rng));
+        assert_eq!(sim.settlements[0].population(), 19);
+        assert_eq!(sim.migrations.len(), 1);
+        assert_eq!(sim.migrations[0].region, Region::new(1, 0));
+
+        for day in 2..6 {
+            sim.advance(day, &mut rng);
+        }
+        // one a day leaves, and each arrives three regions later
+        assert_eq!(sim.settlements[1].population(), 2);
+        assert_eq!(sim.migrations.len(), 3);
+    }
+}
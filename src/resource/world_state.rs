@@ -1,13 +1,16 @@
+use ndarray::Array2;
 use rand::prelude::*;
 use rand_pcg::*;
 use serde::{Deserialize, Serialize};
 use specs::{Component, VecStorage};
+use std::ops::{Index, IndexMut};
 use tcod::noise::*;
 
-use crate::component::Region;
+use crate::component::{Direction, Region};
 use crate::constants::*;
-use crate::resource::{Assets, GeographyTemplate};
+use crate::resource::{Assets, GeographyTemplate, QuestLog, RoadCurve, RoadGraph, WorldSim};
 use crate::util::*;
+use std::collections::{HashMap, HashSet};
 
 #[derive(Copy, Clone, Debug)]
 pub struct RoadTile {
@@ -29,62 +32,121 @@ impl Default for RoadTile {
     }
 }
 
-#[derive(Copy, Clone)]
-pub struct RoadMap {
-    tiles: [[RoadTile; WORLD_SIZE]; WORLD_SIZE],
+/// a square layer of per-region data covering the whole world, indexed by
+/// absolute position (see WorldState::to_abs_pos)
+#[derive(Clone)]
+pub struct WorldLayer<T> {
+    cells: Array2<T>,
 }
 
-/// a map of road tiles
-impl Default for RoadMap {
-    fn default() -> RoadMap {
-        RoadMap {
-            tiles: [[RoadTile::default(); WORLD_SIZE]; WORLD_SIZE],
+impl<T: Clone> WorldLayer<T> {
+    /// makes a <size> by <size> layer with every cell set to <fill>
+    pub fn new(size: usize, fill: T) -> WorldLayer<T> {
+        WorldLayer {
+            cells: Array2::from_elem((size, size), fill),
         }
     }
 }
 
-#[derive(Copy, Clone)]
-/// a map of geography template indexes
-pub struct GeographyMap {
-    indexes: [[usize; WORLD_SIZE]; WORLD_SIZE],
+impl<T> Default for WorldLayer<T> {
+    /// an empty layer, to be replaced when the world is generated
+    fn default() -> WorldLayer<T> {
+        WorldLayer {
+            cells: Array2::from_shape_vec((0, 0), Vec::new()).unwrap(),
+        }
+    }
 }
 
-impl Default for GeographyMap {
-    fn default() -> GeographyMap {
-        GeographyMap {
-            indexes: [[0; WORLD_SIZE]; WORLD_SIZE],
-        }
+impl<T> Index<(usize, usize)> for WorldLayer<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        &self.cells[[x, y]]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for WorldLayer<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        &mut self.cells[[x, y]]
     }
 }
 
-#[derive(Copy, Clone)]
+/// a map of road tiles
+pub type RoadMap = WorldLayer<RoadTile>;
+/// a map of geography template indexes
+pub type GeographyMap = WorldLayer<usize>;
 /// a map of geography icons for drawing a world map
-pub struct IconMap {
-    chars: [[char; WORLD_SIZE]; WORLD_SIZE],
+pub type IconMap = WorldLayer<char>;
+
+/// what lies beyond the edge of the world
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum WorldEdge {
+    /// an impassable border, filled with the border geography
+    #[default]
+    Border,
+    /// the world wraps around, so leaving one edge enters the opposite one
+    Wrap,
 }
 
-impl Default for IconMap {
-    fn default() -> IconMap {
-        IconMap {
-            chars: [[' '; WORLD_SIZE]; WORLD_SIZE],
-        }
+/// the kind of water (if any) found in a region
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Hydrology {
+    /// no water at all
+    Dry,
+    /// dry land bordering a lake or the sea
+    Coast,
+    /// a river runs through the region
+    River,
+    /// the region is covered by a lake
+    Lake,
+    /// the region is open sea
+    Sea,
+}
+
+impl Hydrology {
+    /// whether the whole region is under water
+    pub fn is_body(self) -> bool {
+        self == Hydrology::Lake || self == Hydrology::Sea
     }
 }
 
-#[derive(Copy, Clone)]
-pub struct PopMap {
-    samples: [[f32; WORLD_SIZE]; WORLD_SIZE],
+#[derive(Copy, Clone, Debug)]
+pub struct WaterTile {
+    pub kind: Hydrology,
+    /// the neighboring region a river flows into
+    pub flows_to: Option<Direction>,
 }
 
-impl Default for PopMap {
-    fn default() -> PopMap {
-        PopMap {
-            samples: [[0.0; WORLD_SIZE]; WORLD_SIZE],
+impl Default for WaterTile {
+    fn default() -> WaterTile {
+        WaterTile {
+            kind: Hydrology::Dry,
+            flows_to: None,
         }
     }
 }
 
-#[derive(Copy, Clone, Component, Deserialize, Serialize)]
+/// a map of water by region
+pub type WaterMap = WorldLayer<WaterTile>;
+/// a map of ground elevation, from 0.0 (lowest) to 1.0 (highest)
+pub type HeightMap = WorldLayer<f32>;
+/// a map of how wet each region is, from 0.0 (arid) to 1.0 (sodden)
+pub type MoistureMap = WorldLayer<f32>;
+/// a map of average temperature by region, from 0.0 (coldest) to 1.0 (hottest)
+pub type TemperatureMap = WorldLayer<f32>;
+/// a map of population density, from 0.0 (empty) to 1.0 (crowded)
+pub type PopMap = WorldLayer<f32>;
+
+/// a named place on the world map, like a town or a hive
+#[derive(Clone, Debug)]
+pub struct PointOfInterest {
+    /// the point of interest template it was made from
+    pub template: String,
+    pub name: String,
+    pub region: Region,
+}
+
+#[derive(Clone, Component, Deserialize, Serialize)]
 #[storage(VecStorage)]
 pub struct WorldState {
     /// global RNG seed
@@ -100,6 +162,26 @@ pub struct WorldState {
     /// world size (world is always square)
     size: u32,
 
+    #[serde(default)]
+    /// what happens at the edge of the world
+    pub edge: WorldEdge,
+
+    #[serde(default)]
+    /// the player's standing with each faction that has changed its mind about them
+    reputation: HashMap<String, i32>,
+
+    #[serde(default)]
+    /// story flags set by dialogue and other events
+    flags: HashSet<String>,
+
+    #[serde(default)]
+    /// quests the player has taken on, and how far along they are
+    pub quests: QuestLog,
+
+    #[serde(default)]
+    /// how the world's settlements have fared, simulated while nobody's looking
+    pub sim: WorldSim,
+
     #[serde(skip)]
     /// map of population density, deterministic so skipped when reloading game
     pub pop: PopMap,
@@ -108,6 +190,42 @@ pub struct WorldState {
     /// map of roads, deterministic so skipped when reloading game
     pub roads: RoadMap,
 
+    #[serde(skip)]
+    /// graph of the road network, built from the road map
+    pub road_graph: RoadGraph,
+
+    #[serde(skip)]
+    /// roads running across the grid, like highways between cities
+    pub road_curves: Vec<RoadCurve>,
+
+    #[serde(skip)]
+    /// indexes of the road curves passing through each region
+    curves_at: HashMap<Region, Vec<usize>>,
+
+    #[serde(skip)]
+    /// towns, camps and other notable places, deterministic so skipped when reloading game
+    pub points_of_interest: Vec<PointOfInterest>,
+
+    #[serde(skip)]
+    /// index of the point of interest in each region that has one
+    poi_at: HashMap<Region, usize>,
+
+    #[serde(skip)]
+    /// map of elevation, deterministic so skipped when reloading game
+    pub heights: HeightMap,
+
+    #[serde(skip)]
+    /// map of seas, lakes and rivers, deterministic so skipped when reloading game
+    pub water: WaterMap,
+
+    #[serde(skip)]
+    /// map of moisture, deterministic so skipped when reloading game
+    pub moisture: MoistureMap,
+
+    #[serde(skip)]
+    /// map of temperature, deterministic so skipped when reloading game
+    pub temperature: TemperatureMap,
+
     #[serde(skip)]
     /// map of geographies
     pub geographies: GeographyMap,
@@ -118,27 +236,78 @@ pub struct WorldState {
 
     /// true when the world is ready to be used (after init)
     pub ready: bool,
+
+    #[serde(skip)]
+    /// goes up each time the world is reseeded or loaded, so anything still
+    /// being built from the old one can tell it's stale
+    generation: u32,
 }
 
 impl Default for WorldState {
     fn default() -> WorldState {
+        WorldState::new(WORLD_SIZE as u32)
+    }
+}
+
+impl WorldState {
+    /// makes a new world <size> regions across, with a random seed
+    pub fn new(size: u32) -> WorldState {
         let mut rng = rand::thread_rng();
-        WorldState {
+        let mut world = WorldState {
             seed: rng.gen_range(0, std::u32::MAX),
             day: 0,
             time: 6.0,
             year: 70,
             pop: PopMap::default(),
             roads: RoadMap::default(),
+            road_graph: RoadGraph::default(),
+            road_curves: Vec::new(),
+            curves_at: HashMap::new(),
+            points_of_interest: Vec::new(),
+            poi_at: HashMap::new(),
+            heights: HeightMap::default(),
+            water: WaterMap::default(),
+            moisture: MoistureMap::default(),
+            temperature: TemperatureMap::default(),
             geographies: GeographyMap::default(),
             icons: IconMap::default(),
-            size: WORLD_SIZE as u32,
+            size,
+            edge: WorldEdge::default(),
+            reputation: HashMap::new(),
+            flags: HashSet::new(),
+            quests: QuestLog::default(),
+            sim: WorldSim::default(),
             ready: false,
-        }
+            generation: 0,
+        };
+        world.reset_layers();
+        world
+    }
+
+    /// (re)allocates every map layer to fit the world size, clearing them. Layers
+    /// aren't saved, so this needs to happen before generating a loaded world
+    pub fn reset_layers(&mut self) {
+        let size = self.size as usize;
+        self.pop = PopMap::new(size, 0.0);
+        self.roads = RoadMap::new(size, RoadTile::default());
+        self.road_graph = RoadGraph::default();
+        self.road_curves.clear();
+        self.curves_at.clear();
+        self.points_of_interest.clear();
+        self.poi_at.clear();
+        self.heights = HeightMap::new(size, 0.0);
+        self.water = WaterMap::new(size, WaterTile::default());
+        self.moisture = MoistureMap::new(size, 0.0);
+        self.temperature = TemperatureMap::new(size, 0.0);
+        self.geographies = GeographyMap::new(size, 0);
+        self.icons = IconMap::new(size, ' ');
+    }
+
+    /// the length of a side of the world, in regions
+    pub fn size(&self) -> u32 {
+        self.size
     }
-}
 
-impl WorldState {
     /// needs a getter because changing this causes the world to change ready state
     pub fn seed(&self) -> u32 {
         self.seed
@@ -147,7 +316,44 @@ impl WorldState {
     /// needs a setter because changing this causes the world to change ready state
     pub fn set_seed(&mut self, seed: u32) {
         self.seed = seed;
+        // a different world has different settlements
+        self.sim = WorldSim::default();
         self.ready = false;
+        self.generation = self.generation.wrapping_add(1);
+    }
+
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    /// swaps in a world loaded from a save, ready to be generated again
+    pub fn load(&mut self, loaded: WorldState) {
+        let generation = self.generation.wrapping_add(1);
+        *self = loaded;
+        self.reset_layers();
+        self.ready = false;
+        self.generation = generation;
+    }
+
+    /// moves the clock forward by <hours>, rolling over into new days and years
+    pub fn advance_time(&mut self, hours: f32) {
+        self.time += hours;
+        while self.time >= 24.0 {
+            self.time -= 24.0;
+            self.day += 1;
+        }
+        if self.day >= 365 {
+            if (self.year + 1) % 4 == 0 {
+                // it was a leap year! but don't make the first year a leap year, that would be lame
+                if self.day >= 366 {
+                    self.day = 0;
+                    self.year += 1;
+                }
+            } else {
+                self.day = 0;
+                self.year += 1;
+            }
+        }
     }
 
     /// 1.0 is noon, 0.0 is midnight
@@ -155,40 +361,190 @@ impl WorldState {
         ((self.time * 15.0 * (std::f32::consts::PI / 180.0)).sin() + 1.0) / 2.0
     }
 
-    pub fn to_abs_pos(&self, region: Region) -> (usize, usize) {
-        let x = region.x + (self.size as i32 / 2);
-        let y = region.y + (self.size as i32 / 2);
-        assert!(x >= 0, "got a negative x in set_road");
-        assert!(y >= 0, "got a negative y in set_road");
-        assert!((x as usize) < WORLD_SIZE, "got an oversized x in set_road");
-        assert!((y as usize) < WORLD_SIZE, "got an oversized y in set_road");
-        (x as usize, y as usize)
+    /// gets the position of <region> in the world's map layers, or None if it's
+    /// beyond the edge of a bordered world. Wrapping worlds always have a position
+    pub fn to_abs_pos(&self, region: Region) -> Option<(usize, usize)> {
+        let size = self.size as i32;
+        let x = region.x - self.min_x();
+        let y = region.y - self.min_y();
+        match self.edge {
+            WorldEdge::Wrap => Some((x.rem_euclid(size) as usize, y.rem_euclid(size) as usize)),
+            WorldEdge::Border => {
+                if x >= 0 && y >= 0 && x < size && y < size {
+                    Some((x as usize, y as usize))
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    /// whether a region has any world data. False for regions beyond the edge
+    /// of a bordered world
+    pub fn contains(&self, region: Region) -> bool {
+        self.to_abs_pos(region).is_some()
     }
 
     pub fn get_icon(&self, region: Region) -> char {
-        let (x, y) = self.to_abs_pos(region);
-        self.icons.chars[x][y]
+        self.to_abs_pos(region).map_or(' ', |pos| self.icons[pos])
     }
 
     pub fn set_pop(&mut self, region: Region, density: f32) {
-        let (x, y) = self.to_abs_pos(region);
-        self.pop.samples[x][y] = density;
+        if let Some(pos) = self.to_abs_pos(region) {
+            self.pop[pos] = density;
+        }
     }
 
     pub fn get_pop(&self, region: Region) -> f32 {
-        let (x, y) = self.to_abs_pos(region);
-        self.pop.samples[x][y]
+        self.to_abs_pos(region).map_or(0.0, |pos| self.pop[pos])
     }
 
     pub fn get_road(&self, region: Region) -> RoadTile {
-        let (x, y) = self.to_abs_pos(region);
-        self.roads.tiles[x][y]
+        self.to_abs_pos(region)
+            .map_or(RoadTile::default(), |pos| self.roads[pos])
     }
 
     pub fn set_road(&mut self, region: Region, lanes_x: u8, lanes_y: u8) {
-        let (x, y) = self.to_abs_pos(region);
-        self.roads.tiles[x][y].lanes_x = lanes_x;
-        self.roads.tiles[x][y].lanes_y = lanes_y;
+        if let Some(pos) = self.to_abs_pos(region) {
+            self.roads[pos] = RoadTile { lanes_x, lanes_y };
+        }
+    }
+
+    /// <region> as the rest of the world knows it, keeping its level. Regions
+    /// past the edge of a wrapping world are the ones they wrap around to, and
+    /// anything past a border stays where it is
+    pub fn wrap(&self, region: Region) -> Region {
+        self.canonical(region)
+            .map_or(region, |canonical| canonical.on_level(region.z))
+    }
+
+    /// the same region inside the world, which only differs from <region> in a
+    /// wrapping world
+    pub fn canonical(&self, region: Region) -> Option<Region> {
+        self.to_abs_pos(region)
+            .map(|(x, y)| Region::new(x as i32 + self.min_x(), y as i32 + self.min_y()))
+    }
+
+    pub fn add_road_curve(&mut self, curve: RoadCurve) {
+        let index = self.road_curves.len();
+        for region in curve.regions() {
+            if let Some(region) = self.canonical(region) {
+                let indexes = self.curves_at.entry(region).or_default();
+                if !indexes.contains(&index) {
+                    indexes.push(index);
+                }
+            }
+        }
+        self.road_curves.push(curve);
+    }
+
+    /// indexes of the road curves passing through <region>
+    pub fn curve_indexes(&self, region: Region) -> &[usize] {
+        match self.canonical(region).and_then(|r| self.curves_at.get(&r)) {
+            Some(indexes) => indexes,
+            None => &[],
+        }
+    }
+
+    /// whether a road curve runs straight from region <a> into region <b>
+    pub fn curves_link(&self, a: Region, b: Region) -> bool {
+        let into = self.curve_indexes(b);
+        self.curve_indexes(a)
+            .iter()
+            .any(|index| into.contains(index))
+    }
+
+    /// the names of every road running through <region>
+    pub fn road_names(&self, region: Region) -> Vec<&str> {
+        let mut names = self.road_graph.names_at(region);
+        for index in self.curve_indexes(region) {
+            let name = self.road_curves[*index].name.as_str();
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        names
+    }
+
+    /// the player's standing with <faction>, from -100 to 100
+    pub fn get_reputation(&self, assets: &Assets, faction: &str) -> i32 {
+        match self.reputation.get(faction) {
+            Some(standing) => *standing,
+            None => assets.get_faction(faction).map_or(0, |f| f.reputation),
+        }
+    }
+
+    /// raises or lowers (when <change> is negative) the player's standing with <faction>
+    pub fn change_reputation(&mut self, assets: &Assets, faction: &str, change: i32) {
+        let standing = (self.get_reputation(assets, faction) + change).clamp(-100, 100);
+        self.reputation.insert(faction.to_string(), standing);
+    }
+
+    pub fn set_flag(&mut self, flag: &str) {
+        self.flags.insert(flag.to_string());
+    }
+
+    /// all the flags set so far
+    pub fn flags(&self) -> &HashSet<String> {
+        &self.flags
+    }
+
+    /// adds a point of interest, replacing any already in its region
+    pub fn add_point_of_interest(&mut self, poi: PointOfInterest) {
+        if let Some(region) = self.canonical(poi.region) {
+            self.poi_at.insert(region, self.points_of_interest.len());
+            self.points_of_interest.push(poi);
+        }
+    }
+
+    /// the point of interest in <region>, if there is one
+    pub fn get_point_of_interest(&self, region: Region) -> Option<&PointOfInterest> {
+        self.canonical(region)
+            .and_then(|r| self.poi_at.get(&r))
+            .map(|index| &self.points_of_interest[*index])
+    }
+
+    pub fn get_height(&self, region: Region) -> f32 {
+        self.to_abs_pos(region).map_or(0.0, |pos| self.heights[pos])
+    }
+
+    pub fn set_height(&mut self, region: Region, height: f32) {
+        if let Some(pos) = self.to_abs_pos(region) {
+            self.heights[pos] = height;
+        }
+    }
+
+    pub fn get_water(&self, region: Region) -> WaterTile {
+        self.to_abs_pos(region)
+            .map_or(WaterTile::default(), |pos| self.water[pos])
+    }
+
+    pub fn set_water(&mut self, region: Region, kind: Hydrology, flows_to: Option<Direction>) {
+        if let Some(pos) = self.to_abs_pos(region) {
+            self.water[pos] = WaterTile { kind, flows_to };
+        }
+    }
+
+    pub fn get_moisture(&self, region: Region) -> f32 {
+        self.to_abs_pos(region)
+            .map_or(0.0, |pos| self.moisture[pos])
+    }
+
+    pub fn set_moisture(&mut self, region: Region, moisture: f32) {
+        if let Some(pos) = self.to_abs_pos(region) {
+            self.moisture[pos] = moisture;
+        }
+    }
+
+    pub fn get_temperature(&self, region: Region) -> f32 {
+        self.to_abs_pos(region)
+            .map_or(0.0, |pos| self.temperature[pos])
+    }
+
+    pub fn set_temperature(&mut self, region: Region, temperature: f32) {
+        if let Some(pos) = self.to_abs_pos(region) {
+            self.temperature[pos] = temperature;
+        }
     }
 
     pub fn min_x(&self) -> i32 {
@@ -196,7 +552,7 @@ impl WorldState {
     }
 
     pub fn max_x(&self) -> i32 {
-        (self.size as i32 / 2) as i32
+        self.min_x() + self.size as i32
     }
 
     pub fn min_y(&self) -> i32 {
@@ -204,7 +560,7 @@ impl WorldState {
     }
 
     pub fn max_y(&self) -> i32 {
-        (self.size as i32 / 2) as i32
+        self.min_y() + self.size as i32
     }
 
     /// chooses a random geography based on a random number <selector>
@@ -212,20 +568,41 @@ impl WorldState {
         let sample: f32 =
             rand_up(noise.get_fbm([region.x as f32 * 0.1, region.y as f32 * 0.1], 16));
         let pop = self.get_pop(region);
-        let choices: Vec<(usize, &GeographyTemplate)> = assets
+        let water = self.get_water(region).kind;
+        let climate = [
+            self.get_height(region),
+            self.get_moisture(region),
+            self.get_temperature(region),
+        ];
+        let candidates: Vec<(usize, &GeographyTemplate)> = assets
             .get_geographies()
             .values()
             .enumerate()
-            .filter(|item| item.1.population_range[0] <= pop && item.1.population_range[1] >= pop)
+            .filter(|item| !item.1.border)
+            .filter(|item| item.1.fits_population(pop))
+            .filter(|item| item.1.fits_hydrology(water))
             .collect();
+        let mut choices: Vec<(usize, &GeographyTemplate)> = candidates
+            .iter()
+            .filter(|item| item.1.fits_climate(climate[0], climate[1], climate[2]))
+            .cloned()
+            .collect();
+        if choices.is_empty() {
+            // nothing suits the climate exactly, so settle for anything that fits
+            // the population and water
+            choices = candidates;
+        }
         let len = choices.len() as f32;
         let choice = *choices
             .get((len * (sample % len)).floor() as usize)
             .expect("no available geographies matching the given tag");
-        let (x, y) = self.to_abs_pos(region);
-        self.geographies.indexes[x][y] = choice.0;
+        let (x, y) = match self.to_abs_pos(region) {
+            Some(pos) => pos,
+            None => return,
+        };
+        self.geographies[(x, y)] = choice.0;
         if let Some(icon) = &choice.1.icon {
-            self.icons.chars[x][y] = assets
+            self.icons[(x, y)] = assets
                 .get_icon(&icon.name)
                 .variant(noise.get_fbm([region.x as f32, region.y as f32], 8))
                 .ch()
@@ -233,21 +610,38 @@ impl WorldState {
     }
 
     pub fn get_geography_from_assets(&self, assets: &Assets, region: Region) -> GeographyTemplate {
-        let (x, y) = self.to_abs_pos(region);
-        let index = self.geographies.indexes[x][y];
-        let geographies: Vec<&GeographyTemplate> = assets.get_geographies().values().collect();
-        if let Some(geography) = geographies.get(index) {
-            return (*geography).clone();
-        } else {
-            GeographyTemplate::default()
+        self.geography(assets, region).cloned().unwrap_or_default()
+    }
+
+    /// the geography template for <region>, borrowed from <assets>
+    pub fn geography<'a>(
+        &self,
+        assets: &'a Assets,
+        region: Region,
+    ) -> Option<&'a GeographyTemplate> {
+        match self.to_abs_pos(region) {
+            Some(pos) => assets.get_geographies().values().nth(self.geographies[pos]),
+            // beyond the edge of the world
+            None => assets
+                .get_geographies()
+                .values()
+                .find(|geography| geography.border),
         }
     }
 
     /// gets a seed based on the world seed and the given region
     pub fn region_seed(&self, region: Region) -> u64 {
         // TODO is this too sloppy? probably works fine
-        let off = region.to_unsigned();
-        (u64::from(self.seed) / 32) + (off[0] << 3) + off[1]
+        // regions in a wrapping world repeat, as do their seeds
+        let off = match self.to_abs_pos(region) {
+            Some((x, y)) => [x as u64, y as u64],
+            None => [
+                (i64::from(region.x) - i64::from(self.min_x())) as u64,
+                (i64::from(region.y) - i64::from(self.min_y())) as u64,
+            ],
+        };
+        let level = (i64::from(region.z) as u64) << 32;
+        ((u64::from(self.seed) / 32) + (off[0] << 3) + off[1]).wrapping_add(level)
     }
 
     /// makes a region-specific RNG
@@ -255,3 +649,53 @@ impl WorldState {
         Pcg32::seed_from_u64(self.region_seed(region))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn world_size() {
+        let mut world = WorldState::new(8);
+        assert_eq!((world.min_x(), world.max_x()), (-4, 4));
+        let corners = [
+            Region::new(-4, -4),
+            Region::new(3, -4),
+            Region::new(-4, 3),
+            Region::new(3, 3),
+        ];
+        for (i, region) in corners.iter().enumerate() {
+            assert!(world.contains(*region));
+            world.set_pop(*region, i as f32);
+            world.set_road(*region, i as u8, 0);
+        }
+        for (i, region) in corners.iter().enumerate() {
+            assert_eq!(world.get_pop(*region), i as f32);
+            assert_eq!(world.get_road(*region).lanes_x, i as u8);
+        }
+        assert!(!world.contains(Region::new(4, 0)));
+        assert_eq!(world.wrap(Region::new(4, 0)), Region::new(4, 0));
+
+        // everything beyond a border is empty
+        assert_eq!(world.get_road(Region::new(-5, 0)).lanes_x, 0);
+        world.set_pop(Region::new(0, 100), 1.0);
+
+        // wrapping worlds repeat in every direction
+        world.edge = WorldEdge::Wrap;
+        assert!(world.contains(Region::new(4, 0)));
+        assert_eq!(world.get_pop(Region::new(4, 12)), 0.0);
+        assert_eq!(world.get_pop(Region::new(-5, -5)), 3.0);
+        assert_eq!(
+            world.region_seed(Region::new(11, 11)),
+            world.region_seed(corners[3])
+        );
+        assert_eq!(
+            world.wrap(Region::new(11, 11).on_level(-1)),
+            corners[3].on_level(-1)
+        );
+
+        // resetting clears the layers
+        world.reset_layers();
+        assert_eq!(world.get_pop(corners[3]), 0.0);
+    }
+}
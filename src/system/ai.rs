@@ -1,25 +1,226 @@
-use crate::component::ai_brain::MovementBehavior;
+use crate::component::ai_brain::{Attitude, MovementBehavior};
 use crate::component::*;
-use crate::resource::{RegionMap, GameState};
+use crate::constants::{MAP_HEIGHT, MAP_WIDTH};
+use crate::resource::{
+    Assets, GameEvent, GameEvents, GameStage, GameState, RegionMaps, WorldState,
+    SAME_FACTION_STANDING,
+};
 use rand::prelude::*;
-use specs::{Join, Read, ReadStorage, System, WriteStorage};
+use specs::{Entities, Entity, Join, Read, ReadStorage, System, Write, WriteStorage};
+
+/// how far away NPCs notice others, in tiles
+const SIGHT_RANGE: i32 = 12;
+
+/// someone an NPC might have feelings about
+struct Actor {
+    entity: Entity,
+    /// where it is in world tile coordinates
+    at: [i32; 2],
+    /// the level it's on
+    z: i32,
+    faction: Option<String>,
+    player: bool,
+    body: u8,
+    /// share of an observer's sight range it can be spotted at
+    visibility: f32,
+    /// how far away it can be heard, in tiles
+    noise: i32,
+}
+
+/// the standing of faction <own> toward faction <other>
+fn standing(assets: &Assets, own: &str, other: &str) -> i32 {
+    match assets.get_faction(own) {
+        Some(template) => template.standing_toward(own, other),
+        None if own == other => SAME_FACTION_STANDING,
+        None => 0,
+    }
+}
+
+/// <pos> in <region> in world tile coordinates, so actors can size each other
+/// up across region borders
+fn world_pos(region: Region, pos: Pos) -> [i32; 2] {
+    let offset = region.to_offset();
+    [offset[0] + pos.x as i32, offset[1] + pos.y as i32]
+}
+
+fn distance(a: [i32; 2], b: [i32; 2]) -> i32 {
+    (a[0] - b[0]).abs().max((a[1] - b[1]).abs())
+}
+
+/// whether an observer at <at> spots or hears <other>
+fn notices(at: [i32; 2], perception: f32, other: &Actor) -> bool {
+    let distance = distance(at, other.at);
+    distance as f32 <= SIGHT_RANGE as f32 * perception * other.visibility || distance <= other.noise
+}
+
+/// a one tile step from <from> toward <to>
+fn step_toward(from: [i32; 2], to: [i32; 2]) -> MovePlan {
+    MovePlan {
+        x: (to[0] - from[0]).signum(),
+        y: (to[1] - from[1]).signum(),
+    }
+}
+
+/// whether the tile <x>, <y> from the corner of <region> can be walked on. It
+/// may be over the border in a neighbor, which only the ground level has
+fn walkable(maps: &RegionMaps, region: Region, x: i32, y: i32) -> bool {
+    let (width, height) = (MAP_WIDTH as i32, MAP_HEIGHT as i32);
+    let inside = (0..width).contains(&x) && (0..height).contains(&y);
+    if !inside && region.z != 0 {
+        return false;
+    }
+    let [wx, wy] = world_pos(region, Pos::default());
+    let (wx, wy) = (wx + x, wy + y);
+    let there = Region {
+        x: wx.div_euclid(width),
+        y: wy.div_euclid(height),
+        z: region.z,
+    };
+    let pos = Pos::new(
+        wx.rem_euclid(width) as usize,
+        wy.rem_euclid(height) as usize,
+    );
+    maps.get_tile(there, pos).map(|t| t.walkable) == Some(true)
+}
 
 pub struct AI;
 impl<'a> System<'a> for AI {
     type SystemData = (
+        Entities<'a>,
         WriteStorage<'a, AIBrain>,
         ReadStorage<'a, Pos>,
+        ReadStorage<'a, Region>,
+        ReadStorage<'a, Faction>,
+        ReadStorage<'a, Player>,
+        ReadStorage<'a, Description>,
+        ReadStorage<'a, Template>,
+        WriteStorage<'a, Character>,
         WriteStorage<'a, MovePlan>,
-        Read<'a, RegionMap>,
-        Read<'a, GameState>,
+        WriteStorage<'a, Energy>,
+        ReadStorage<'a, MovementMode>,
+        Read<'a, RegionMaps>,
+        Read<'a, Assets>,
+        Read<'a, WorldState>,
+        Write<'a, GameState>,
+        Write<'a, GameEvents>,
     );
 
-    fn run(&mut self, (mut brains, positions, mut plans, map, state): Self::SystemData) {
+    fn run(
+        &mut self,
+        (
+            entities,
+            mut brains,
+            positions,
+            regions,
+            factions,
+            players,
+            descriptions,
+            templates,
+            mut characters,
+            mut plans,
+            mut energies,
+            modes,
+            maps,
+            assets,
+            world,
+            mut state,
+            mut events,
+        ): Self::SystemData,
+    ) {
         let mut rng = rand::thread_rng();
         if !state.ticking {
             return;
         } // AI only runs on ticks
-        for (mut brain, pos, mut plan) in (&mut brains, &positions, &mut plans).join() {
+        let actors: Vec<Actor> = (&entities, &positions, &regions)
+            .join()
+            .filter(|(entity, ..)| {
+                factions.get(*entity).is_some() || players.get(*entity).is_some()
+            })
+            .map(|(entity, pos, region)| Actor {
+                entity,
+                at: world_pos(*region, *pos),
+                z: region.z,
+                faction: factions.get(entity).map(|f| f.name.clone()),
+                player: players.get(entity).is_some(),
+                body: characters.get(entity).map_or(0, |c| c.body()),
+                visibility: modes.get(entity).map_or(1.0, |m| m.visibility()),
+                noise: modes.get(entity).map_or(0, |m| m.noise()),
+            })
+            .collect();
+        let mut attacks: Vec<(Entity, Entity)> = Vec::new();
+        for (entity, brain, pos, region, plan, energy) in (
+            &entities,
+            &mut brains,
+            &positions,
+            &regions,
+            &mut plans,
+            (&mut energies).maybe(),
+        )
+            .join()
+        {
+            // actors still recovering from their last action sit this turn out
+            let mut cost = WAIT_COST;
+            if let Some(false) = energy.as_ref().map(|e| e.ready()) {
+                continue;
+            }
+            let at = world_pos(*region, *pos);
+            // decide how to feel about whoever is nearby, paying the most attention
+            // to anyone worth attacking or fleeing from
+            if let Some(faction) = factions.get(entity) {
+                let body = characters.get(entity).map_or(0, |c| c.body());
+                let perception = modes.get(entity).map_or(1.0, |m| m.perception());
+                let noticed = actors
+                    .iter()
+                    .filter(|other| other.entity != entity && other.z == region.z)
+                    .filter(|other| notices(at, perception, other))
+                    .map(|other| {
+                        let standing = if other.player {
+                            world.get_reputation(&assets, &faction.name)
+                        } else {
+                            match &other.faction {
+                                Some(name) => standing(&assets, &faction.name, name),
+                                None => 0,
+                            }
+                        };
+                        (Attitude::from_standing(standing, body, other.body), other)
+                    })
+                    .min_by_key(|(attitude, other)| {
+                        (!attitude.is_urgent(), distance(at, other.at))
+                    });
+                match noticed {
+                    Some((attitude, other)) if attitude.is_urgent() => {
+                        brain.attitude = attitude;
+                        brain.target = Some(other.entity);
+                        brain.movement_state = if attitude == Attitude::Hostile {
+                            MovementBehavior::Pursue
+                        } else {
+                            MovementBehavior::Flee
+                        };
+                    }
+                    // stop for the player, who might want a word
+                    Some((Attitude::Friendly, other)) if other.player => {
+                        brain.attitude = Attitude::Friendly;
+                        brain.target = Some(other.entity);
+                        brain.movement_state = MovementBehavior::Linger;
+                    }
+                    _ => {
+                        brain.attitude =
+                            noticed.map_or(Attitude::Passive, |(attitude, _)| attitude);
+                        brain.target = None;
+                        match brain.movement_state {
+                            MovementBehavior::Pursue
+                            | MovementBehavior::Flee
+                            | MovementBehavior::Linger => {
+                                brain.movement_state = MovementBehavior::Idle;
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+            }
+            let target = brain
+                .target
+                .and_then(|target| actors.iter().find(|a| a.entity == target));
             match brain.movement_state {
                 MovementBehavior::BrownianWalk => {
                     let mut tries: i8 = 0;
@@ -33,9 +234,11 @@ impl<'a> System<'a> for AI {
                             x: pos.x as i32 + to.x,
                             y: pos.y as i32 + to.y,
                         };
-                        if map.get(Pos::from(target)).map_or(false, |t| t.walkable) {
+                        // wandering off the edge leads into the neighboring region
+                        if walkable(&maps, *region, target.x, target.y) {
                             plan.x = to.x;
                             plan.y = to.y;
+                            cost = MOVE_COST;
                             done = true;
                         }
                         tries += 1;
@@ -43,14 +246,66 @@ impl<'a> System<'a> for AI {
                     if !done {
                         // right now, just waits for the turn, then tries again
                         brain.movement_state = MovementBehavior::Idle;
-                        break;
                     }
                 }
                 MovementBehavior::Idle => {
                     brain.movement_state = MovementBehavior::BrownianWalk;
-                    break;
                 }
-                _ => {}
+                MovementBehavior::Pursue => {
+                    if let Some(target) = target {
+                        if distance(at, target.at) <= 1 {
+                            attacks.push((entity, target.entity));
+                            cost = ATTACK_COST;
+                        } else {
+                            *plan = step_toward(at, target.at);
+                            cost = MOVE_COST;
+                        }
+                    }
+                }
+                MovementBehavior::Flee => {
+                    if let Some(target) = target {
+                        *plan = step_toward(target.at, at);
+                        cost = MOVE_COST;
+                    }
+                }
+                MovementBehavior::Linger => {}
+            }
+            if let Some(energy) = energy {
+                energy.spend(cost);
+            }
+        }
+        for (attacker, target) in attacks {
+            let damage = characters.get(attacker).map_or(1, |c| c.strength().max(1));
+            // already killed by someone else this turn
+            let character = match characters.get_mut(target) {
+                Some(character) => character,
+                None => continue,
+            };
+            character.wound(damage);
+            let killed = character.stamina().1 == 0;
+            if players.get(target).is_some() {
+                let name = descriptions
+                    .get(attacker)
+                    .map_or("something".to_string(), |d| d.short.clone());
+                events.single_write(GameEvent::Notified {
+                    title: "Attacked".to_string(),
+                    body: format!("{} attacks you for {} damage.", name, damage),
+                });
+                // the player stays put, so the game can be loaded from the last save
+                if killed {
+                    events.single_write(GameEvent::Notified {
+                        title: "Killed".to_string(),
+                        body: format!("{} kills you. [F6] loads your last save.", name),
+                    });
+                    state.stage = GameStage::Dead;
+                }
+            } else if killed {
+                events.single_write(GameEvent::Killed {
+                    template: templates.get(target).map(|t| t.name.clone()),
+                });
+                entities
+                    .delete(target)
+                    .expect("tried to delete a non-existent entity");
             }
         }
     }
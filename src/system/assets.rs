@@ -5,24 +5,33 @@ use std::path::Path;
 
 use crate::constants::{ICON_DIR, TEMPLATE_DIR};
 use crate::resource::{
-    Assets, EntityTemplate, GameStage, GameState, GeographyTemplate, IconSet, StructureTemplate,
+    Assets, DialogueTemplate, EntityTemplate, FactionTemplate, GameStage, GameState,
+    GeographyTemplate, IconSet, PoiTemplate, QuestTemplate, StructureTemplate,
 };
 
 fn type_dir(template_type: AssetType) -> String {
     match template_type {
         AssetType::IconSet => format!("{}/{}", ICON_DIR, "icons"),
+        AssetType::Dialogue => format!("{}/{}", TEMPLATE_DIR, "dialogues"),
         AssetType::Entity => format!("{}/{}", TEMPLATE_DIR, "entities"),
+        AssetType::Faction => format!("{}/{}", TEMPLATE_DIR, "factions"),
         AssetType::Geography => format!("{}/{}", TEMPLATE_DIR, "geographies"),
         AssetType::Structure => format!("{}/{}", TEMPLATE_DIR, "structures"),
+        AssetType::PointOfInterest => format!("{}/{}", TEMPLATE_DIR, "points_of_interest"),
+        AssetType::Quest => format!("{}/{}", TEMPLATE_DIR, "quests"),
     }
 }
 
 #[derive(Copy, Clone)]
 pub enum AssetType {
     IconSet,
+    Dialogue,
     Entity,
+    Faction,
     Geography,
     Structure,
+    PointOfInterest,
+    Quest,
 }
 
 pub struct AssetLoader {
@@ -51,10 +60,14 @@ impl AssetLoader {
     fn enqueue_assets(&mut self) {
         if self.queue.is_none() {
             let mut queue: Vec<(AssetType, DirEntry)> = Vec::new();
+            self.enqueue_directory(&mut queue, AssetType::Dialogue);
             self.enqueue_directory(&mut queue, AssetType::Entity);
+            self.enqueue_directory(&mut queue, AssetType::Faction);
             self.enqueue_directory(&mut queue, AssetType::IconSet);
             self.enqueue_directory(&mut queue, AssetType::Geography);
             self.enqueue_directory(&mut queue, AssetType::Structure);
+            self.enqueue_directory(&mut queue, AssetType::PointOfInterest);
+            self.enqueue_directory(&mut queue, AssetType::Quest);
             self.queue = Some(queue);
         }
     }
@@ -90,7 +103,7 @@ impl AssetLoader {
         }
     }
 
-    fn process_queue<'a>(&mut self, assets: &mut Write<'a, Assets>) {
+    fn process_queue(&mut self, assets: &mut Assets) {
         if let Some(queue) = &mut self.queue {
             if let Some((template_type, next)) = queue.pop() {
                 println!("reading template from file {:?}", next);
@@ -106,10 +119,21 @@ impl AssetLoader {
                 file.read_to_string(&mut text)
                     .expect("error: could not read template file");
                 match template_type {
+                    AssetType::Dialogue => {
+                        let template: DialogueTemplate = ron::de::from_str(&text).unwrap();
+                        match template.check() {
+                            Ok(()) => assets.add_dialogue(&name, template),
+                            Err(err) => println!("skipping broken dialogue {}: {}", name, err),
+                        }
+                    }
                     AssetType::Entity => {
                         let template: EntityTemplate = ron::de::from_str(&text).unwrap();
                         assets.add_entity(&name, template);
                     }
+                    AssetType::Faction => {
+                        let template: FactionTemplate = ron::de::from_str(&text).unwrap();
+                        assets.add_faction(&name, template);
+                    }
                     AssetType::IconSet => {
                         let set: IconSet = ron::de::from_str(&text).unwrap();
                         for (name, icon) in set.process().drain(..) {
@@ -123,15 +147,39 @@ impl AssetLoader {
                     AssetType::Structure => {
                         let mut template: StructureTemplate = ron::de::from_str(&text).unwrap();
                         template.init();
+                        let dir = type_dir(AssetType::Structure);
+                        if let Err(err) = template.load_example_image(&dir) {
+                            println!("ignoring example image for {}: {}", name, err);
+                        }
                         assets.add_structure(&name, template);
                     }
+                    AssetType::PointOfInterest => {
+                        let template: PoiTemplate = ron::de::from_str(&text).unwrap();
+                        assets.add_point_of_interest(&name, template);
+                    }
+                    AssetType::Quest => {
+                        let template: QuestTemplate = ron::de::from_str(&text).unwrap();
+                        assets.add_quest(&name, template);
+                    }
                 }
             } else {
                 // run inheritance passes
                 assets.process_geographies();
                 assets.ready = true;
-                println!("finished loading {} assets: {} entities, {} icons, {} geographies, {} structures.", assets.len(), assets.entity_len(), assets.icon_len(), assets.geography_len(), assets.structure_len());
+                println!("finished loading {} assets: {} dialogues, {} entities, {} factions, {} icons, {} geographies, {} structures, {} points of interest, {} quests.", assets.len(), assets.dialogue_len(), assets.entity_len(), assets.faction_len(), assets.icon_len(), assets.geography_len(), assets.structure_len(), assets.point_of_interest_len(), assets.quest_len());
             }
         }
     }
 }
+
+/// every asset, loaded all at once for tests that need the real thing
+#[cfg(test)]
+pub fn load_assets() -> Assets {
+    let mut loader = AssetLoader::default();
+    let mut assets = Assets::default();
+    loader.enqueue_assets();
+    while !assets.ready {
+        loader.process_queue(&mut assets);
+    }
+    assets
+}
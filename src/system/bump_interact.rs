@@ -1,7 +1,7 @@
 use crate::component::*;
-use crate::resource::InteractionTarget;
+use crate::resource::{GameEvent, GameEvents};
 /// handles auto-interact when a player bumps against a solid
-use specs::{Join, ReadStorage, System, Write, WriteStorage};
+use specs::{Entities, Join, ReadStorage, System, Write, WriteStorage};
 
 pub struct BumpInteract;
 impl<'a> System<'a> for BumpInteract {
@@ -10,28 +10,49 @@ impl<'a> System<'a> for BumpInteract {
         ReadStorage<'a, MovePlan>,
         ReadStorage<'a, Solid>,
         ReadStorage<'a, NotificationInteraction>,
+        ReadStorage<'a, Dialogue>,
+        ReadStorage<'a, Region>,
         WriteStorage<'a, Pos>,
-        Write<'a, InteractionTarget>,
+        Write<'a, GameEvents>,
+        Entities<'a>,
     );
 
     fn run(
         &mut self,
-        (players, plans, solids, interactions, mut positions, mut target): Self::SystemData,
+        (
+            players,
+            plans,
+            solids,
+            interactions,
+            dialogues,
+            regions,
+            mut positions,
+            mut events,
+            entities,
+        ): Self::SystemData,
     ) {
         // look up player position & plan
         let mut p_pos: Pos = Pos::default();
         let mut p_plan: MovePlan = MovePlan::default();
-        for (plan, pos, _player) in (&plans, &positions, &players).join() {
+        let mut p_region: Region = Region::default();
+        for (plan, pos, region, _player) in (&plans, &positions, &regions, &players).join() {
             if plan.x == 0 && plan.y == 0 {
                 return;
             } // player isn't moving
             p_pos = *pos;
             p_plan = *plan;
+            p_region = *region;
         }
-        for (pos, _interaction, _solid) in (&mut positions, &interactions, &solids).join() {
+        for (entity, pos, region, _solid) in (&entities, &mut positions, &regions, &solids).join() {
+            if interactions.get(entity).is_none() && dialogues.get(entity).is_none() {
+                continue;
+            }
+            // other loaded regions and levels use the same local positions
+            if *region != p_region {
+                continue;
+            }
             if p_plan + p_pos == *pos {
-                println!("found a bump interaction target at {:?}", pos);
-                target.pos = Some(*pos);
+                events.single_write(GameEvent::Bumped { pos: *pos });
             }
         }
     }
@@ -0,0 +1,92 @@
+/// starts conversations with NPCs the player interacts with
+use crate::component::{Character, Description, Dialogue, Player, Pos, Region};
+use crate::resource::{
+    subscribe, Assets, DialogueContext, DialogueWidget, GameEvent, GameEvents, GameState,
+    InteractionMethod, UIQueue, WorldState,
+};
+use specs::{Join, Read, ReadStorage, ReaderId, Resources, System, SystemData, Write};
+
+#[derive(Default)]
+pub struct Talk {
+    reader: Option<ReaderId<GameEvent>>,
+}
+
+impl<'a> System<'a> for Talk {
+    type SystemData = (
+        ReadStorage<'a, Region>,
+        ReadStorage<'a, Pos>,
+        ReadStorage<'a, Dialogue>,
+        ReadStorage<'a, Description>,
+        ReadStorage<'a, Player>,
+        ReadStorage<'a, Character>,
+        Write<'a, GameEvents>,
+        Write<'a, UIQueue>,
+        Read<'a, GameState>,
+        Read<'a, Assets>,
+        Read<'a, WorldState>,
+    );
+
+    fn setup(&mut self, res: &mut Resources) {
+        Self::SystemData::setup(res);
+        self.reader = Some(subscribe(res));
+    }
+
+    fn run(
+        &mut self,
+        (
+            regions,
+            positions,
+            dialogues,
+            descriptions,
+            players,
+            characters,
+            mut events,
+            mut queue,
+            state,
+            assets,
+            world,
+        ): Self::SystemData,
+    ) {
+        let methods = [InteractionMethod::Check, InteractionMethod::Talk];
+        let t_pos = match &mut self.reader {
+            // only one conversation at a time
+            Some(reader) => match events.read(reader).filter_map(|e| e.wants(&methods)).last() {
+                Some(pos) => pos,
+                None => return,
+            },
+            None => return,
+        };
+        for (e_region, e_pos, dialogue, description) in
+            (&regions, &positions, &dialogues, descriptions.maybe()).join()
+        {
+            if *e_pos != t_pos || *e_region != state.region {
+                continue;
+            }
+            let speaker = description.map_or("someone".to_string(), |d| d.short.clone());
+            // missing, or skipped for being broken
+            let template = match assets.get_dialogue(&dialogue.name) {
+                Some(template) => template.clone(),
+                None => {
+                    events.single_write(GameEvent::Notified {
+                        title: "Talk".to_string(),
+                        body: format!("{} has nothing to say.", speaker),
+                    });
+                    return;
+                }
+            };
+            let mut context = DialogueContext::default();
+            if let Some((character, _player)) = (&characters, &players).join().next() {
+                context.character = *character;
+            }
+            context.flags = world.flags().clone();
+            for name in assets.get_factions().keys() {
+                context
+                    .reputation
+                    .insert(name.clone(), world.get_reputation(&assets, name));
+            }
+            queue.add(DialogueWidget::new(speaker, template, context));
+            events.single_write(GameEvent::Talked(dialogue.name.clone()));
+            return;
+        }
+    }
+}
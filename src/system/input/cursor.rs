@@ -1,5 +1,5 @@
-use crate::component::{Cursor, MovePlan, Pos};
-use crate::resource::{GameState, InteractionTarget, UserInput};
+use crate::component::{Cursor, MovePlan, Pos, Region};
+use crate::resource::{GameEvent, GameEvents, GameState, Travel, TravelGoal, UserInput};
 use specs::{Entities, Join, ReadStorage, System, Write, WriteStorage};
 use tcod::input::Key;
 use tcod::input::KeyCode::*;
@@ -11,11 +11,13 @@ pub struct CursorInput;
 impl<'a> System<'a> for CursorInput {
     type SystemData = (
         ReadStorage<'a, Pos>,
+        ReadStorage<'a, Region>,
         WriteStorage<'a, MovePlan>,
         WriteStorage<'a, Cursor>,
         Write<'a, UserInput>,
         Write<'a, GameState>,
-        Write<'a, InteractionTarget>,
+        Write<'a, GameEvents>,
+        Write<'a, Travel>,
         Entities<'a>,
     );
 
@@ -23,21 +25,30 @@ impl<'a> System<'a> for CursorInput {
         &mut self,
         (
       positions,
+      regions,
       mut plans,
       cursors,
       mut input,
       mut state,
-      mut target,
+      mut events,
+      mut travel,
       entities): Self::SystemData,
     ) {
         state.looking = false;
 
-        for (pos, to, entity, _) in (&positions, &mut plans, &entities, &cursors).join() {
+        for (pos, region, to, entity, _) in
+            (&positions, &regions, &mut plans, &entities, &cursors).join()
+        {
             state.looking = true;
 
             if let Some(plan) = get_movement(&input) {
-                to.x = plan.x;
-                to.y = plan.y;
+                // the cursor isn't blocked by anything, so it can skip along
+                let speed = match input.get() {
+                    Some(Key { shift: true, .. }) => 2,
+                    _ => 1,
+                };
+                to.x = plan.x * speed;
+                to.y = plan.y * speed;
             }
 
             match input.get() {
@@ -50,7 +61,22 @@ impl<'a> System<'a> for CursorInput {
                 | Some(Key {
                     code: NumPadEnter, ..
                 }) => {
-                    target.pos = Some(*pos);
+                    events.single_write(GameEvent::MenuRequested(*pos));
+                    entities
+                        .delete(entity)
+                        .expect("tried to delete a non-existent cursor");
+                }
+                // travel to the cursor, wherever it's been moved to
+                Some(Key {
+                    code: Char,
+                    printable: 't',
+                    ..
+                }) => {
+                    travel.start(TravelGoal::To {
+                        region: *region,
+                        pos: Some(*pos),
+                    });
+                    state.fast_forward = true;
                     entities
                         .delete(entity)
                         .expect("tried to delete a non-existent cursor");
@@ -0,0 +1,31 @@
+use crate::resource::{Assets, GameState, QuestLogWidget, UIQueue, UserInput, WorldState};
+use specs::{Read, System, Write};
+use tcod::input::Key;
+use tcod::input::KeyCode::*;
+
+/// opens the quest log
+pub struct JournalInput;
+impl<'a> System<'a> for JournalInput {
+    type SystemData = (
+        Write<'a, UserInput>,
+        Write<'a, UIQueue>,
+        Read<'a, GameState>,
+        Read<'a, WorldState>,
+        Read<'a, Assets>,
+    );
+
+    fn run(&mut self, (mut input, mut queue, state, world, assets): Self::SystemData) {
+        if state.paused || queue.len() > 0 {
+            return;
+        }
+        if let Some(Key {
+            code: Char,
+            printable: 'j',
+            ..
+        }) = input.get()
+        {
+            queue.add(QuestLogWidget::new(&world.quests, &assets));
+            input.consume();
+        }
+    }
+}
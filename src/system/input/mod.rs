@@ -1,12 +1,16 @@
 mod cursor;
 mod fallthrough;
+mod journal;
 mod movement_util;
 mod player;
 mod system;
 mod ui;
+mod world_map;
 
 pub use cursor::CursorInput;
 pub use fallthrough::FallthroughInput;
+pub use journal::JournalInput;
 pub use player::PlayerInput;
 pub use system::SystemInput;
 pub use ui::UIInput;
+pub use world_map::WorldMapInput;
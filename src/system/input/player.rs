@@ -1,5 +1,10 @@
-use crate::component::{Cursor, Direction, MovePlan, Orientation, Player, Pos, Region};
-use crate::resource::{GameState, UserInput};
+use crate::component::{
+    Cursor, Direction, MovePlan, MovementMode, Orientation, Player, Pos, Region,
+};
+use crate::resource::{
+    CollisionMaps, GameEvent, GameEvents, GameState, RegionMaps, Stairs, Travel, TravelGoal,
+    UserInput,
+};
 use specs::{Entities, Join, Read, ReadStorage, System, Write, WriteStorage};
 use tcod::input::Key;
 use tcod::input::KeyCode::*;
@@ -14,10 +19,15 @@ impl<'a> System<'a> for PlayerInput {
         WriteStorage<'a, Orientation>,
         WriteStorage<'a, Pos>,
         WriteStorage<'a, MovePlan>,
+        WriteStorage<'a, MovementMode>,
         ReadStorage<'a, Player>,
         WriteStorage<'a, Region>,
-        Read<'a, GameState>,
+        Read<'a, RegionMaps>,
+        Write<'a, CollisionMaps>,
+        Write<'a, GameState>,
         Write<'a, UserInput>,
+        Write<'a, GameEvents>,
+        Write<'a, Travel>,
         Entities<'a>,
     );
 
@@ -28,10 +38,15 @@ impl<'a> System<'a> for PlayerInput {
             mut orientations,
             mut positions,
             mut plans,
+            mut modes,
             players,
             mut regions,
-            state,
+            maps,
+            mut collision_maps,
+            mut state,
             mut input,
+            mut events,
+            mut travel,
             entities,
         ): Self::SystemData,
     ) {
@@ -40,7 +55,15 @@ impl<'a> System<'a> for PlayerInput {
         if state.paused {
             return;
         } // no moving while paused
-        for (pos, to, region, ..) in (&positions, &mut plans, &mut regions, &players).join() {
+        for (pos, to, region, mode, ..) in (
+            &positions,
+            &mut plans,
+            &mut regions,
+            (&mut modes).maybe(),
+            &players,
+        )
+            .join()
+        {
             player_pos = *pos;
             player_region = *region;
             if let Some(plan) = get_movement(&input) {
@@ -49,9 +72,45 @@ impl<'a> System<'a> for PlayerInput {
                 input.consume();
                 return;
             }
+            if let (Some(mode), Some(next)) = (mode, get_movement_mode(&input)) {
+                *mode = if *mode == next {
+                    MovementMode::Walk
+                } else {
+                    next
+                };
+                events.single_write(GameEvent::Notified {
+                    title: "Pace".to_string(),
+                    body: format!("You are now {}.", mode.name()),
+                });
+                input.consume();
+                return;
+            }
+            if let Some(stairs) = get_stairs(&input) {
+                input.consume();
+                take_stairs(
+                    stairs,
+                    *pos,
+                    region,
+                    &maps,
+                    &mut collision_maps,
+                    &mut state,
+                    &mut events,
+                );
+                return;
+            }
         }
 
         match input.get() {
+            // auto-explore
+            Some(Key {
+                code: Char,
+                printable: 'x',
+                ..
+            }) => {
+                travel.start(TravelGoal::Explore);
+                state.fast_forward = true;
+                input.consume();
+            }
             Some(Key { code: Enter, .. })
             | Some(Key {
                 code: NumPadEnter, ..
@@ -60,13 +119,7 @@ impl<'a> System<'a> for PlayerInput {
                 entities
                     .build_entity()
                     .with(Cursor, &mut cursors)
-                    .with(
-                        Region {
-                            x: player_region.x,
-                            y: player_region.y,
-                        },
-                        &mut regions,
-                    )
+                    .with(player_region, &mut regions)
                     .with(MovePlan::default(), &mut plans)
                     .with(
                         Pos {
@@ -91,3 +144,78 @@ impl<'a> System<'a> for PlayerInput {
         }
     }
 }
+
+/// the movement mode to switch to, switching back to walking if it's the current one
+fn get_movement_mode(input: &UserInput) -> Option<MovementMode> {
+    match input.get() {
+        Some(Key {
+            code: Char,
+            printable: 'r',
+            shift: true,
+            ..
+        }) => Some(MovementMode::Sprint),
+        Some(Key {
+            code: Char,
+            printable: 'r',
+            ..
+        }) => Some(MovementMode::Run),
+        Some(Key {
+            code: Char,
+            printable: 's',
+            ..
+        }) => Some(MovementMode::Sneak),
+        _ => None,
+    }
+}
+
+fn get_stairs(input: &UserInput) -> Option<Stairs> {
+    match input.get() {
+        Some(Key {
+            code: Char,
+            printable: '<',
+            ..
+        }) => Some(Stairs::Up),
+        Some(Key {
+            code: Char,
+            printable: '>',
+            ..
+        }) => Some(Stairs::Down),
+        _ => None,
+    }
+}
+
+/// moves the player to the next level if they're standing on the matching stairs
+fn take_stairs(
+    stairs: Stairs,
+    pos: Pos,
+    region: &mut Region,
+    maps: &RegionMaps,
+    collision_maps: &mut CollisionMaps,
+    state: &mut GameState,
+    events: &mut GameEvents,
+) {
+    if !maps.has(*region) || maps.get(*region).get(pos).and_then(|t| t.stairs) != Some(stairs) {
+        return;
+    }
+    let destination = region.on_level(region.z + stairs.level_change());
+    if !maps.has(destination) || collision_maps.get(destination, pos) {
+        return;
+    }
+    collision_maps.set(*region, pos, false);
+    collision_maps.set(destination, pos, true);
+    events.single_write(GameEvent::RegionChanged);
+    *region = destination;
+    state.region = destination;
+    let direction = match stairs {
+        Stairs::Up => "up",
+        Stairs::Down => "down",
+    };
+    events.single_write(GameEvent::Notified {
+        title: "Stairs".to_string(),
+        body: format!(
+            "You head {} the stairs: {}.",
+            direction,
+            destination.level_name()
+        ),
+    });
+}
@@ -50,6 +50,9 @@ impl<'a> System<'a> for SystemInput {
                         state.map_mode = MapMode::Terrain;
                     }
                     MapMode::Terrain => {
+                        state.map_mode = MapMode::Population;
+                    }
+                    MapMode::Population => {
                         state.map_mode = MapMode::Hybrid;
                     }
                 }
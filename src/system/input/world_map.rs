@@ -0,0 +1,29 @@
+use crate::resource::{GameState, UIQueue, UserInput, WorldMapWidget};
+use specs::{Read, System, Write};
+use tcod::input::Key;
+use tcod::input::KeyCode::*;
+
+/// opens the world map
+pub struct WorldMapInput;
+impl<'a> System<'a> for WorldMapInput {
+    type SystemData = (
+        Write<'a, UserInput>,
+        Write<'a, UIQueue>,
+        Read<'a, GameState>,
+    );
+
+    fn run(&mut self, (mut input, mut queue, state): Self::SystemData) {
+        if state.paused || queue.len() > 0 {
+            return;
+        }
+        if let Some(Key {
+            code: Char,
+            printable: 'w',
+            ..
+        }) = input.get()
+        {
+            queue.add(WorldMapWidget::new(state.region));
+            input.consume();
+        }
+    }
+}
@@ -1,45 +1,470 @@
 /// support player interactions with objects
-use crate::component::{NotificationInteraction, Pos, Region};
-use crate::resource::{GameState, InteractionMethod, InteractionTarget, Notification, UIQueue};
+use crate::component::*;
+use crate::resource::{
+    subscribe, Assets, ContextMenu, Explored, GameEvent, GameEvents, GameState, InteractionMethod,
+    RegionMaps, UIQueue, WorldState,
+};
+use rand::prelude::*;
+use specs::{
+    Entities, Entity, Join, Read, ReadStorage, ReaderId, Resources, System, SystemData, Write,
+    WriteStorage,
+};
 
+/// chance of turning something up when searching the ground
+const SEARCH_CHANCE: f32 = 0.2;
+/// what searching the ground can turn up
+const SEARCH_FINDS: [&str; 4] = [
+    "a bit of scrap",
+    "a bent nail",
+    "a length of wire",
+    "a bottle cap",
+];
+/// how much reputation is lost with a faction for attacking one of its members
+const ATTACK_REPUTATION: i32 = -20;
+
+/// the positions the player asked to use one of <methods> on since <reader> last
+/// looked
+fn wanted(
+    events: &GameEvents,
+    reader: &mut Option<ReaderId<GameEvent>>,
+    methods: &[InteractionMethod],
+) -> Vec<Pos> {
+    match reader {
+        Some(reader) => events
+            .read(reader)
+            .filter_map(|event| event.wants(methods))
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+fn notify(events: &mut GameEvents, title: &str, body: String) {
+    events.single_write(GameEvent::Notified {
+        title: title.to_string(),
+        body,
+    });
+}
+
+/// the first entity at <pos> in the player's region with all the joined components
+fn entity_at<'a, J: Join>(
+    state: &GameState,
+    pos: Pos,
+    regions: &ReadStorage<'a, Region>,
+    positions: &ReadStorage<'a, Pos>,
+    join: J,
+) -> Option<J::Type> {
+    (regions, positions, join)
+        .join()
+        .find(|(region, e_pos, _)| **e_pos == pos && **region == state.region)
+        .map(|(_, _, joined)| joined)
+}
+
+/// shows a menu of the verbs available for a position, when asked for one
 #[derive(Default)]
-pub struct Notify;
+pub struct VerbMenu {
+    reader: Option<ReaderId<GameEvent>>,
+}
 
-use specs::{Join, Read, ReadStorage, System, Write};
-impl<'a> System<'a> for Notify {
+impl<'a> System<'a> for VerbMenu {
     type SystemData = (
+        Entities<'a>,
         ReadStorage<'a, Region>,
         ReadStorage<'a, Pos>,
+        ReadStorage<'a, Description>,
+        ReadStorage<'a, Player>,
+        ReadStorage<'a, Character>,
+        ReadStorage<'a, Dialogue>,
+        ReadStorage<'a, Item>,
+        ReadStorage<'a, Container>,
         ReadStorage<'a, NotificationInteraction>,
-        Write<'a, InteractionTarget>,
+        ReadStorage<'a, Verbs>,
+        Read<'a, GameEvents>,
         Write<'a, UIQueue>,
         Read<'a, GameState>,
+        Read<'a, RegionMaps>,
     );
 
+    fn setup(&mut self, res: &mut Resources) {
+        Self::SystemData::setup(res);
+        self.reader = Some(subscribe(res));
+    }
+
     fn run(
         &mut self,
-        (regions, positions, notifications, mut target, mut queue, state): Self::SystemData,
+        (
+            entities,
+            regions,
+            positions,
+            descriptions,
+            players,
+            characters,
+            dialogues,
+            items,
+            containers,
+            notifications,
+            verbs,
+            events,
+            mut queue,
+            state,
+            maps,
+        ): Self::SystemData,
     ) {
-        let mut matched: bool = false;
-        if let Some(t_pos) = target.pos {
-            for (e_region, e_pos, notice) in (&regions, &positions, &notifications).join() {
-                if matched {
-                    break;
+        let requests: Vec<Pos> = match &mut self.reader {
+            Some(reader) => events
+                .read(reader)
+                .filter_map(|event| match event {
+                    GameEvent::MenuRequested(pos) => Some(*pos),
+                    _ => None,
+                })
+                .collect(),
+            None => return,
+        };
+        for pos in requests {
+            let mut available: Vec<InteractionMethod> = Vec::new();
+            let mut title = String::new();
+            if let Some(entity) = entity_at(&state, pos, &regions, &positions, &entities) {
+                if let Some(description) = descriptions.get(entity) {
+                    title = description.short.clone();
+                }
+                match verbs.get(entity) {
+                    Some(verbs) => available.extend(verbs.available.iter()),
+                    None => {
+                        let has = |present: bool, verb: InteractionMethod| {
+                            if present {
+                                Some(verb)
+                            } else {
+                                None
+                            }
+                        };
+                        available.extend(
+                            [
+                                has(
+                                    descriptions.get(entity).is_some(),
+                                    InteractionMethod::Examine,
+                                ),
+                                has(dialogues.get(entity).is_some(), InteractionMethod::Talk),
+                                has(notifications.get(entity).is_some(), InteractionMethod::Use),
+                                has(containers.get(entity).is_some(), InteractionMethod::Open),
+                                has(items.get(entity).is_some(), InteractionMethod::PickUp),
+                                has(
+                                    characters.get(entity).is_some()
+                                        && players.get(entity).is_none(),
+                                    InteractionMethod::Attack,
+                                ),
+                            ]
+                            .iter()
+                            .flatten(),
+                        );
+                    }
+                }
+            }
+            let tile = if maps.has(state.region) {
+                maps.get(state.region).get(pos)
+            } else {
+                None
+            };
+            if let Some(tile) = tile {
+                if title.is_empty() {
+                    title = tile.description.short.clone();
                 }
-                match target.method {
-                    InteractionMethod::Check => {
-                        if *e_pos == t_pos && *e_region == state.region {
-                            queue.add(Notification::new(
-                                notice.header.clone(),
-                                notice.body.clone(),
-                            ));
-                            matched = true;
-                        }
+                for verb in &[InteractionMethod::Examine, InteractionMethod::Search] {
+                    if !available.contains(verb) {
+                        available.push(*verb);
                     }
                 }
             }
+            if !available.is_empty() {
+                queue.add(ContextMenu::new(title, pos, available));
+            }
+        }
+    }
+}
+
+/// shows the description of the targeted entity, or else the tile
+#[derive(Default)]
+pub struct Examine {
+    reader: Option<ReaderId<GameEvent>>,
+}
+
+impl<'a> System<'a> for Examine {
+    type SystemData = (
+        ReadStorage<'a, Region>,
+        ReadStorage<'a, Pos>,
+        ReadStorage<'a, Description>,
+        Write<'a, GameEvents>,
+        Read<'a, GameState>,
+        Read<'a, RegionMaps>,
+    );
+
+    fn setup(&mut self, res: &mut Resources) {
+        Self::SystemData::setup(res);
+        self.reader = Some(subscribe(res));
+    }
+
+    fn run(
+        &mut self,
+        (regions, positions, descriptions, mut events, state, maps): Self::SystemData,
+    ) {
+        for pos in wanted(&events, &mut self.reader, &[InteractionMethod::Examine]) {
+            let description = match entity_at(&state, pos, &regions, &positions, &descriptions) {
+                Some(description) => description.clone(),
+                None if maps.has(state.region) => match maps.get(state.region).get(pos) {
+                    Some(tile) => tile.description.clone(),
+                    None => continue,
+                },
+                None => continue,
+            };
+            notify(&mut events, &description.short, description.long);
+        }
+    }
+}
+
+/// the player attacks the targeted character, who dies when out of stamina
+#[derive(Default)]
+pub struct Attack {
+    reader: Option<ReaderId<GameEvent>>,
+}
+
+impl<'a> System<'a> for Attack {
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, Region>,
+        ReadStorage<'a, Pos>,
+        ReadStorage<'a, Player>,
+        ReadStorage<'a, Faction>,
+        ReadStorage<'a, Description>,
+        ReadStorage<'a, Template>,
+        WriteStorage<'a, Character>,
+        Write<'a, GameEvents>,
+        Read<'a, GameState>,
+        Read<'a, Assets>,
+        Write<'a, WorldState>,
+    );
+
+    fn setup(&mut self, res: &mut Resources) {
+        Self::SystemData::setup(res);
+        self.reader = Some(subscribe(res));
+    }
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            regions,
+            positions,
+            players,
+            factions,
+            descriptions,
+            templates,
+            mut characters,
+            mut events,
+            state,
+            assets,
+            mut world,
+        ): Self::SystemData,
+    ) {
+        for pos in wanted(&events, &mut self.reader, &[InteractionMethod::Attack]) {
+            let victim: Entity = match entity_at(&state, pos, &regions, &positions, &entities) {
+                Some(entity) if players.get(entity).is_none() => entity,
+                _ => continue,
+            };
+            let damage = match (&players, &characters).join().next() {
+                Some((_, character)) => character.strength().max(1),
+                None => continue,
+            };
+            let character = match characters.get_mut(victim) {
+                Some(character) => character,
+                None => continue,
+            };
+            character.wound(damage);
+            let name = descriptions
+                .get(victim)
+                .map_or("something".to_string(), |d| d.short.clone());
+            notify(&mut events, "Attack", format!("You attack {}.", name));
+            if let Some(faction) = factions.get(victim) {
+                world.change_reputation(&assets, &faction.name, ATTACK_REPUTATION);
+            }
+            if character.stamina().1 == 0 {
+                notify(&mut events, "Killed", format!("{} dies.", name));
+                events.single_write(GameEvent::Killed {
+                    template: templates.get(victim).map(|t| t.name.clone()),
+                });
+                entities
+                    .delete(victim)
+                    .expect("tried to delete a non-existent entity");
+            }
+        }
+    }
+}
+
+/// the player picks up the targeted item
+#[derive(Default)]
+pub struct PickUp {
+    reader: Option<ReaderId<GameEvent>>,
+}
+
+impl<'a> System<'a> for PickUp {
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, Region>,
+        ReadStorage<'a, Pos>,
+        ReadStorage<'a, Player>,
+        ReadStorage<'a, Item>,
+        WriteStorage<'a, Inventory>,
+        Write<'a, GameEvents>,
+        Read<'a, GameState>,
+    );
+
+    fn setup(&mut self, res: &mut Resources) {
+        Self::SystemData::setup(res);
+        self.reader = Some(subscribe(res));
+    }
+
+    fn run(
+        &mut self,
+        (entities, regions, positions, players, items, mut inventories, mut events, state): Self::SystemData,
+    ) {
+        for pos in wanted(&events, &mut self.reader, &[InteractionMethod::PickUp]) {
+            if let Some((entity, item)) =
+                entity_at(&state, pos, &regions, &positions, (&entities, &items))
+            {
+                for (_player, inventory) in (&players, &mut inventories).join() {
+                    inventory.add(&item.name);
+                }
+                events.single_write(GameEvent::PickedUp);
+                notify(
+                    &mut events,
+                    "Picked up",
+                    format!("You pick up {}.", item.name),
+                );
+                entities
+                    .delete(entity)
+                    .expect("tried to delete a non-existent entity");
+            }
+        }
+    }
+}
+
+/// the player opens the targeted container and takes what's inside
+#[derive(Default)]
+pub struct Open {
+    reader: Option<ReaderId<GameEvent>>,
+}
+
+impl<'a> System<'a> for Open {
+    type SystemData = (
+        ReadStorage<'a, Region>,
+        ReadStorage<'a, Pos>,
+        ReadStorage<'a, Player>,
+        WriteStorage<'a, Container>,
+        WriteStorage<'a, Inventory>,
+        Write<'a, GameEvents>,
+        Read<'a, GameState>,
+    );
+
+    fn setup(&mut self, res: &mut Resources) {
+        Self::SystemData::setup(res);
+        self.reader = Some(subscribe(res));
+    }
+
+    fn run(
+        &mut self,
+        (regions, positions, players, mut containers, mut inventories, mut events, state): Self::SystemData,
+    ) {
+        for pos in wanted(&events, &mut self.reader, &[InteractionMethod::Open]) {
+            let found = match entity_at(&state, pos, &regions, &positions, &mut containers) {
+                Some(container) => std::mem::take(&mut container.items),
+                None => continue,
+            };
+            let body = if found.is_empty() {
+                "It's empty.".to_string()
+            } else {
+                format!("You take {}.", found.join(", "))
+            };
+            for (_player, inventory) in (&players, &mut inventories).join() {
+                for item in &found {
+                    inventory.add(item);
+                }
+            }
+            for _ in found {
+                events.single_write(GameEvent::PickedUp);
+            }
+            notify(&mut events, "Opened", body);
+        }
+    }
+}
+
+/// the player searches the targeted tile, with a chance of finding something.
+/// Each tile can only be searched once
+#[derive(Default)]
+pub struct Search {
+    reader: Option<ReaderId<GameEvent>>,
+}
+
+impl<'a> System<'a> for Search {
+    type SystemData = (
+        ReadStorage<'a, Player>,
+        WriteStorage<'a, Inventory>,
+        Write<'a, GameEvents>,
+        Write<'a, Explored>,
+        Read<'a, GameState>,
+        Read<'a, WorldState>,
+    );
+
+    fn setup(&mut self, res: &mut Resources) {
+        Self::SystemData::setup(res);
+        self.reader = Some(subscribe(res));
+    }
+
+    fn run(
+        &mut self,
+        (players, mut inventories, mut events, mut explored, state, world): Self::SystemData,
+    ) {
+        let mut rng = rand::thread_rng();
+        for pos in wanted(&events, &mut self.reader, &[InteractionMethod::Search]) {
+            let body = if !explored.search(world.wrap(state.region), pos) {
+                "There's nothing left here to find.".to_string()
+            } else if rng.gen_range(0.0, 1.0) < SEARCH_CHANCE {
+                let find = SEARCH_FINDS.choose(&mut rng).unwrap();
+                for (_player, inventory) in (&players, &mut inventories).join() {
+                    inventory.add(find);
+                }
+                events.single_write(GameEvent::PickedUp);
+                format!("You find {}.", find)
+            } else {
+                "You find nothing of use.".to_string()
+            };
+            notify(&mut events, "Searched", body);
+        }
+    }
+}
+
+/// shows the targeted entity's notification when it's checked or used
+#[derive(Default)]
+pub struct Notify {
+    reader: Option<ReaderId<GameEvent>>,
+}
+
+impl<'a> System<'a> for Notify {
+    type SystemData = (
+        ReadStorage<'a, Region>,
+        ReadStorage<'a, Pos>,
+        ReadStorage<'a, NotificationInteraction>,
+        Write<'a, GameEvents>,
+        Read<'a, GameState>,
+    );
+
+    fn setup(&mut self, res: &mut Resources) {
+        Self::SystemData::setup(res);
+        self.reader = Some(subscribe(res));
+    }
+
+    fn run(&mut self, (regions, positions, notifications, mut events, state): Self::SystemData) {
+        let methods = [InteractionMethod::Check, InteractionMethod::Use];
+        for pos in wanted(&events, &mut self.reader, &methods) {
+            if let Some(notice) = entity_at(&state, pos, &regions, &positions, &notifications) {
+                notify(&mut events, &notice.header, notice.body.clone());
+            }
         }
-        target.pos = None;
-        target.entity = None;
     }
 }
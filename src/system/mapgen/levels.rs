@@ -0,0 +1,187 @@
+//! Upper floors and basements for multi-storey structures. These are generated
+//! along with the ground floor and stored as separate maps, one per level.
+use super::connect_tiles::connect;
+use super::structure::{build_structure, Storeys};
+use super::MapGenBundle;
+use crate::component::{Color, Description, Pos};
+use crate::resource::{PrefabOrientation, RegionMap, Stairs, StructureTemplate, Tile};
+use crate::util::Rect;
+use rand::prelude::*;
+use std::collections::HashMap;
+
+/// the tile covering a level outside of any structure: open air above the
+/// ground, solid earth below it
+fn empty_tile(z: i32) -> Tile {
+    if z > 0 {
+        Tile {
+            walkable: false,
+            description: Description::new("open air", "Nothing but a long drop."),
+            ..Tile::default()
+        }
+    } else {
+        Tile {
+            bg: Color::new(24, 20, 16),
+            transparent: false,
+            walkable: false,
+            description: Description::new("earth", "Packed earth and old rubble."),
+            ..Tile::default()
+        }
+    }
+}
+
+/// makes an empty map for level <z>
+fn new_level(bundle: &MapGenBundle, z: i32) -> RegionMap {
+    let mut map = RegionMap::default();
+    let tile = empty_tile(z);
+    for pos in map.bounds().iter() {
+        map.unchecked_set(pos, tile.clone());
+    }
+    map.geography = bundle.map.geography.clone();
+    map.populated = true;
+    map
+}
+
+fn stairs_tile(bundle: &MapGenBundle, stairs: Stairs, floor: &Tile) -> Tile {
+    let (icon, short, long) = match stairs {
+        Stairs::Up => ("stairs_up", "stairs up", "A flight of stairs leading up."),
+        Stairs::Down => (
+            "stairs_down",
+            "stairs down",
+            "A flight of stairs leading down.",
+        ),
+    };
+    Tile {
+        icon: bundle.assets.get_icon(icon).ch(),
+        fg: Color::new(192, 192, 192),
+        bg: floor.bg,
+        constructed: true,
+        description: Description::new(short, long),
+        stairs: Some(stairs),
+        ..Tile::default()
+    }
+}
+
+/// gets the map for level <z>, where 0 is the ground floor
+fn level_map<'a>(bundle: &'a mut MapGenBundle, z: i32) -> Option<&'a mut RegionMap> {
+    if z == 0 {
+        Some(bundle.map)
+    } else {
+        bundle.levels.get_mut(&z)
+    }
+}
+
+/// positions in <bounds> where stairs could go on level <z>
+fn stair_candidates(bundle: &mut MapGenBundle, z: i32, bounds: &Rect<usize>) -> Vec<Pos> {
+    match level_map(bundle, z) {
+        Some(map) => bounds
+            .iter()
+            .filter(|pos| match map.get(*pos) {
+                Some(tile) => tile.walkable && tile.stairs.is_none(),
+                None => false,
+            })
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+/// joins level <lower> to the level above it with a pair of stairs
+/// # Errors
+/// Returns an error if the two levels have no open tile in common for the stairs.
+fn place_stairs(
+    bundle: &mut MapGenBundle,
+    lower: i32,
+    bounds: &Rect<usize>,
+    rng: &mut impl Rng,
+) -> Result<(), &'static str> {
+    let upper = lower + 1;
+    let below = stair_candidates(bundle, lower, bounds);
+    let above = stair_candidates(bundle, upper, bounds);
+    let candidates: Vec<Pos> = below
+        .into_iter()
+        .filter(|pos| above.contains(pos))
+        .collect();
+    let pos = *candidates
+        .choose(rng)
+        .ok_or("no room for stairs between levels")?;
+    for (z, stairs) in &[(lower, Stairs::Up), (upper, Stairs::Down)] {
+        let floor = match level_map(bundle, *z).and_then(|map| map.get(pos)) {
+            Some(tile) => tile.clone(),
+            None => continue,
+        };
+        let tile = stairs_tile(bundle, *stairs, &floor);
+        if let Some(map) = level_map(bundle, *z) {
+            map.unchecked_set(pos, tile);
+        }
+    }
+    Ok(())
+}
+
+/// Builds the upper floors and basements of a structure placed in <bounds> on
+/// the ground floor, turned the same way, and joins them with stairs.
+/// # Errors
+/// Returns an error if a pair of levels couldn't be joined.
+pub fn build_levels(
+    bundle: &mut MapGenBundle,
+    structure: &StructureTemplate,
+    bounds: Rect<usize>,
+    orientation: PrefabOrientation,
+) -> Result<(), &'static str> {
+    let levels = structure.extra_levels();
+    if levels.is_empty() {
+        return Ok(());
+    }
+    for z in &levels {
+        let mut map = match bundle.levels.remove(z) {
+            Some(map) => map,
+            None => new_level(bundle, *z),
+        };
+        let level_bundle = MapGenBundle {
+            assets: bundle.assets,
+            map: &mut map,
+            noise: &mut *bundle.noise,
+            region: bundle.region.on_level(*z),
+            world: bundle.world,
+            geography: bundle.geography,
+            levels: HashMap::new(),
+            spawns: Vec::new(),
+        };
+        // structures nested in other levels only get the one level
+        let storeys = &mut Storeys::new();
+        if let Some(grid) =
+            build_structure(&level_bundle, structure, bounds, orientation, 0, storeys)
+        {
+            level_bundle.map.paste_into(Pos::new(0, 0), grid).ok();
+        }
+        bundle.levels.insert(*z, map);
+    }
+
+    // stairs go between each pair of levels, somewhere inside the walls
+    let mut interior = bounds;
+    interior.shrink_perimeter(structure.perimeter);
+    let rng = &mut bundle.world.region_rng(bundle.region);
+    let lowest = levels[0].min(0);
+    let highest = levels[levels.len() - 1].max(0);
+    for lower in lowest..highest {
+        place_stairs(bundle, lower, &interior, rng)?;
+    }
+    Ok(())
+}
+
+/// connects tiles on every level built so far
+pub fn connect_levels(bundle: &mut MapGenBundle) {
+    let mut levels: Vec<(i32, RegionMap)> = bundle.levels.drain().collect();
+    for (z, map) in levels.iter_mut() {
+        let mut level_bundle = MapGenBundle {
+            assets: bundle.assets,
+            map,
+            noise: &mut *bundle.noise,
+            region: bundle.region.on_level(*z),
+            world: bundle.world,
+            geography: bundle.geography,
+            levels: HashMap::new(),
+            spawns: Vec::new(),
+        };
+        connect(&mut level_bundle);
+    }
+    bundle.levels.extend(levels);
+}
@@ -1,16 +1,24 @@
-use crate::component::Region;
+use crate::component::{Pos, Region, Template};
 use crate::resource::{
-    Assets, GameStage, GameState, GeographyTemplate, RegionMap, RegionMaps, WorldState,
+    Assets, CollisionMaps, EntityStore, GameStage, GameState, GeographyTemplate, RegionMap,
+    RegionMaps, WorldState,
 };
+use specs::world::EntitiesRes;
+use std::collections::{HashMap, HashSet};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::Arc;
 use tcod::noise::*;
 use tcod::random::{Algo, Rng};
 
 mod connect_tiles;
 mod ground_cover;
+mod levels;
+mod poi;
 mod roads;
 mod structure;
 mod trees;
 pub mod util;
+mod water;
 
 use connect_tiles::connect;
 
@@ -24,94 +32,330 @@ pub struct MapGenBundle<'a> {
     region: Region,
     world: &'a WorldState,
     geography: &'a GeographyTemplate,
+    /// upper floors and basements built along with this map, by level
+    levels: HashMap<i32, RegionMap>,
+    /// entities to spawn once the map is done, by template name
+    spawns: Vec<(String, Pos)>,
 }
 
-pub struct MapGenerator {}
+/// everything a map generation pass produces besides the map itself
+struct Generated {
+    levels: HashMap<i32, RegionMap>,
+    spawns: Vec<(String, Pos)>,
+}
+
+/// a map finished by the worker pool, waiting to be put in play
+struct Finished {
+    region: Region,
+    /// the generation of the world it was built for
+    generation: u32,
+    map: RegionMap,
+    generated: Generated,
+}
+
+/// generates region maps on rayon's worker pool, closest to the player first, so
+/// the neighbors are ready before the player crosses into them
+pub struct MapGenerator {
+    /// ground floor regions being generated right now
+    pending: HashSet<Region>,
+    sender: Sender<Finished>,
+    receiver: Receiver<Finished>,
+    /// what the workers build from. The world is copied again every day, as its
+    /// settlements change
+    assets: Option<Arc<Assets>>,
+    world: Option<(u32, Arc<WorldState>)>,
+    /// the generation of the world being built for
+    generation: u32,
+}
 
 impl MapGenerator {
     pub fn new() -> MapGenerator {
-        MapGenerator {}
+        let (sender, receiver) = channel();
+        MapGenerator {
+            pending: HashSet::new(),
+            sender,
+            receiver,
+            assets: None,
+            world: None,
+            generation: 0,
+        }
     }
 }
 
-use specs::{Read, System, Write};
+use specs::{Builder, Entities, LazyUpdate, Read, System, Write};
 impl<'a> System<'a> for MapGenerator {
     type SystemData = (
+        Entities<'a>,
+        Read<'a, LazyUpdate>,
         Read<'a, Assets>,
         Write<'a, RegionMaps>,
+        Write<'a, CollisionMaps>,
+        Write<'a, EntityStore>,
         Write<'a, GameState>,
         Read<'a, WorldState>,
     );
 
-    fn run(&mut self, (assets, mut maps, state, world): Self::SystemData) {
+    fn run(
+        &mut self,
+        (entities, lazy, assets, mut maps, mut collision_maps, mut store, state, world): Self::SystemData,
+    ) {
         if state.stage == GameStage::LoadingAssets {
             return;
         } // don't try to build map while assets loading
-        for (region, map) in maps.iter_mut() {
-            if !map.populated {
-                self.generate(*region, map, &assets, &world);
-                return; // only do one per pass, so we can show progress
+
+        // the world was reseeded or loaded, so anything built from it is stale
+        if !world.ready || world.generation() != self.generation {
+            self.generation = world.generation();
+            self.pending.clear();
+            self.world = None;
+        }
+        if !world.ready {
+            return;
+        }
+
+        // put finished maps in play
+        while let Ok(finished) = self.receiver.try_recv() {
+            let region = finished.region;
+            // built from an older world, which pending has already forgotten
+            if finished.generation != self.generation {
+                continue;
             }
+            self.pending.remove(&region);
+            // pruned or regenerated while it was being built
+            if !maps.has(region) || maps.ready(region) {
+                continue;
+            }
+            maps.insert(region, finished.map);
+            Placement {
+                entities: &entities,
+                lazy: &lazy,
+                maps: &mut maps,
+                collision_maps: &mut collision_maps,
+                store: &mut store,
+            }
+            .place(region, finished.generated, &assets, &world);
+        }
+
+        // hand out whatever still needs building
+        let assets = self
+            .assets
+            .get_or_insert_with(|| Arc::new(assets.clone()))
+            .clone();
+        let snapshot = match &self.world {
+            Some((day, snapshot)) if *day == world.day => snapshot.clone(),
+            _ => {
+                let snapshot = Arc::new(world.clone());
+                self.world = Some((world.day, snapshot.clone()));
+                snapshot
+            }
+        };
+        // other levels are built along with the ground floor
+        let mut waiting: Vec<Region> = maps
+            .regions()
+            .filter(|region| region.z == 0 && !maps.ready(*region))
+            .filter(|region| !self.pending.contains(region))
+            .collect();
+        let center = state.region;
+        waiting.sort_by_key(|r| (r.x - center.x).abs().max((r.y - center.y).abs()));
+        for region in waiting {
+            self.pending.insert(region);
+            let (assets, world, sender) = (assets.clone(), snapshot.clone(), self.sender.clone());
+            let generation = self.generation;
+            rayon::spawn(move || {
+                let mut map = RegionMap::default();
+                let generated = generate(region, &mut map, &assets, &world);
+                // nobody's listening if the game has closed
+                sender
+                    .send(Finished {
+                        region,
+                        generation,
+                        map,
+                        generated,
+                    })
+                    .ok();
+            });
         }
     }
 }
 
-impl MapGenerator {
-    fn generate(
-        &mut self,
-        region: Region,
-        map: &mut RegionMap,
-        assets: &Assets,
-        world: &WorldState,
-    ) {
-        let seed = world.seed();
-        println!(
-            "Generating new map with dimensions {}x{}, seed {} for region {:?}",
-            map.width(),
-            map.height(),
-            seed,
-            region
-        );
-        let trng = Rng::new_with_seed(Algo::CMWC, world.seed());
-        let noise = &mut Noise::init_with_dimensions(2)
-            .noise_type(NoiseType::Simplex)
-            .random(trng)
-            .init();
-
-        // choose a geography variant
-        let geography = &world.get_geography_from_assets(assets, region).clone();
-
-        let bundle = &mut MapGenBundle {
-            assets,
-            map,
-            noise,
-            region,
-            world,
-            geography,
-        };
+/// what a finished map is put in play with
+struct Placement<'a> {
+    entities: &'a EntitiesRes,
+    lazy: &'a LazyUpdate,
+    maps: &'a mut RegionMaps,
+    collision_maps: &'a mut CollisionMaps,
+    store: &'a mut EntityStore,
+}
 
-        // lay down a basic ground cover layer
-        ground_cover::base(bundle, 0.2);
-        ground_cover::scatter(bundle, 1.0);
+impl<'a> Placement<'a> {
+    /// sets up the upper floors, basements and entities that came with a new map
+    fn place(&mut self, region: Region, generated: Generated, assets: &Assets, world: &WorldState) {
+        self.maps.clear_levels(region);
+        let mut loaded = vec![region];
+        for (z, level) in generated.levels {
+            self.maps.insert(region.on_level(z), level);
+            self.collision_maps.init_level(region.on_level(z));
+            loaded.push(region.on_level(z));
+        }
+        // whoever was left here last time comes back instead of a fresh spawn,
+        // less any residents the world simulation has since sent elsewhere
+        let settlement = world
+            .canonical(region)
+            .and_then(|region| world.sim.settlement(region));
+        let mut returned: HashMap<String, u32> = HashMap::new();
+        for level in loaded {
+            for stored in self.store.take(world.wrap(level)) {
+                let count = returned.entry(stored.template().to_string()).or_insert(0);
+                if let Some(residents) = settlement.and_then(|s| s.count(stored.template())) {
+                    if *count >= residents {
+                        continue;
+                    }
+                }
+                *count += 1;
+                stored.restore(level, assets, self.lazy, self.entities);
+            }
+        }
+        let first_visit = self.store.first_visit(world.wrap(region));
+        for (name, pos) in generated.spawns {
+            // back again, only newly arrived residents need spawning
+            if !first_visit {
+                if settlement.and_then(|s| s.count(&name)).is_none() {
+                    continue;
+                }
+                let count = returned.entry(name.clone()).or_insert(0);
+                if *count > 0 {
+                    *count -= 1;
+                    continue;
+                }
+            }
+            match assets.get_entity(&name) {
+                Some(template) => {
+                    template
+                        .to_lazy(self.lazy, self.entities)
+                        .with(pos)
+                        .with(region)
+                        .with(Template::new(&name))
+                        .build();
+                }
+                None => println!("can't spawn unknown entity {}", name),
+            }
+        }
+    }
+}
 
-        let road_data = world.get_road(region);
+/// builds the map for <region>, along with its other levels and what to spawn on it.
+/// Runs on the worker pool, so it only gets a copy of the world
+fn generate(region: Region, map: &mut RegionMap, assets: &Assets, world: &WorldState) -> Generated {
+    // a region wrapped around to is built just like the one it wraps to
+    let region = world.wrap(region);
+    let seed = world.seed();
+    println!(
+        "Generating new map with dimensions {}x{}, seed {} for region {:?}",
+        map.width(),
+        map.height(),
+        seed,
+        region
+    );
+    let trng = Rng::new_with_seed(Algo::CMWC, world.seed());
+    let noise = &mut Noise::init_with_dimensions(2)
+        .noise_type(NoiseType::Simplex)
+        .random(trng)
+        .init();
 
-        if road_data.lanes_x > 0 {
-            roads::place_horizontal_roads(bundle, 0.1, 0.8);
-        }
+    // choose a geography variant
+    let geography = &world.get_geography_from_assets(assets, region).clone();
 
-        if road_data.lanes_y > 0 {
-            roads::place_vertical_roads(bundle, 0.1, 0.8);
-        }
+    let bundle = &mut MapGenBundle {
+        assets,
+        map,
+        noise,
+        region,
+        world,
+        geography,
+        levels: HashMap::new(),
+        spawns: Vec::new(),
+    };
 
-        if bundle.geography.structure_len() > 0 {
-            structure::build(bundle).ok(); // always ok if this fails
-        }
+    // lay down a basic ground cover layer
+    ground_cover::base(bundle, 0.2);
+    ground_cover::scatter(bundle, 1.0);
+
+    // seas, lakes and rivers
+    water::place(bundle);
+
+    let road_data = world.get_road(region);
 
-        // connect connectable tiles
-        connect(bundle);
+    if road_data.lanes_x > 0 {
+        roads::place_horizontal_roads(bundle, 0.1, 0.8);
+    }
+
+    if road_data.lanes_y > 0 {
+        roads::place_vertical_roads(bundle, 0.1, 0.8);
+    }
 
-        // mark map generation done
-        map.populated = true;
+    roads::place_curved_roads(bundle, 0.1, 0.8);
+
+    // towns, camps and the like get their structures first
+    let poi_areas = poi::build(bundle).unwrap_or_default();
+
+    if bundle.geography.structure_len() > 0 {
+        structure::build(bundle).ok(); // always ok if this fails
+    }
+
+    // connect connectable tiles
+    connect(bundle);
+    levels::connect_levels(bundle);
+
+    poi::spawn(bundle, &poi_areas);
+
+    // mark map generation done
+    let generated = Generated {
+        levels: std::mem::take(&mut bundle.levels),
+        spawns: std::mem::take(&mut bundle.spawns),
+    };
+    map.populated = true;
+    generated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::system::assets::load_assets;
+
+    #[test]
+    fn workers_build_the_same_maps() {
+        let assets = Arc::new(load_assets());
+        let mut world = WorldState::new(8);
+        let region = Region::new(1, -1);
+        world.set_road(region, 2, 0);
+        let world = Arc::new(world);
+
+        let mut map = RegionMap::default();
+        let here = generate(region, &mut map, &assets, &world);
+        let (sender, receiver) = channel();
+        let (worker_assets, worker_world) = (assets.clone(), world.clone());
+        rayon::spawn(move || {
+            let mut map = RegionMap::default();
+            let generated = generate(region, &mut map, &worker_assets, &worker_world);
+            sender.send((map, generated)).unwrap();
+        });
+        let (worker_map, there) = receiver.recv().unwrap();
+
+        for y in 0..map.height() {
+            for x in 0..map.width() {
+                let (a, b) = (map.get(Pos::new(x, y)), worker_map.get(Pos::new(x, y)));
+                let (a, b) = (a.unwrap(), b.unwrap());
+                assert_eq!(
+                    (a.icon, a.walkable, a.transparent, a.stairs),
+                    (b.icon, b.walkable, b.transparent, b.stairs)
+                );
+            }
+        }
+        assert_eq!(here.spawns, there.spawns);
+        let mut levels: Vec<&i32> = here.levels.keys().collect();
+        let mut worker_levels: Vec<&i32> = there.levels.keys().collect();
+        levels.sort();
+        worker_levels.sort();
+        assert_eq!(levels, worker_levels);
     }
 }
@@ -0,0 +1,94 @@
+use super::{structure::place_structure, MapGenBundle};
+use crate::component::Pos;
+use crate::util::*;
+use rand::prelude::*;
+
+/// how many spots are tried for each of a point of interest's structures
+const MAX_TRIES: u8 = 30;
+
+/// builds the structures forced into the region by its point of interest, if it
+/// has one. Returns the bounds of each structure built
+pub fn build(bundle: &mut MapGenBundle) -> Result<Vec<Rect<usize>>, &'static str> {
+    let mut built = Vec::new();
+    let template = match bundle
+        .world
+        .get_point_of_interest(bundle.region)
+        .and_then(|poi| bundle.assets.get_point_of_interest(&poi.template))
+    {
+        Some(template) => template,
+        None => return Ok(built),
+    };
+    let rng = &mut bundle.world.region_rng(bundle.region);
+    for name in &template.structures {
+        let structure = match bundle.assets.get_structure(name) {
+            Some(structure) => structure,
+            None => {
+                println!("point of interest structure {} does not exist", name);
+                continue;
+            }
+        };
+        for _ in 0..MAX_TRIES {
+            // these start at 1 to give room for a structure's perimeter tiles
+            let top_left = Pos::new(
+                rng.gen_range(1, bundle.map.width()),
+                rng.gen_range(1, bundle.map.height()),
+            );
+            if let Some(true) = bundle.map.get(top_left).map(|t| t.constructed) {
+                continue;
+            }
+            if let Some(bounds) = place_structure(bundle, structure, top_left)? {
+                built.push(bounds);
+                break;
+            }
+        }
+    }
+    Ok(built)
+}
+
+/// picks spots for the entities of the region's point of interest, inside <areas>
+/// when there are any. Adds them to the bundle's spawn list. How many there are
+/// comes from the world simulation: its residents, and as many supplies lying
+/// around as the place can spare
+pub fn spawn(bundle: &mut MapGenBundle, areas: &[Rect<usize>]) {
+    let template = match bundle
+        .world
+        .get_point_of_interest(bundle.region)
+        .and_then(|poi| bundle.assets.get_point_of_interest(&poi.template))
+    {
+        Some(template) => template,
+        None => return,
+    };
+    let rng = &mut bundle.world.region_rng(bundle.region);
+    let mut spots: Vec<Pos> = if areas.is_empty() {
+        bundle.map.grid.bounds.iter().collect()
+    } else {
+        areas.iter().flat_map(|area| area.iter()).collect()
+    };
+    spots.retain(|pos| {
+        let walkable = bundle.map.get(*pos).map(|t| t.walkable);
+        walkable == Some(true)
+    });
+    spots.shuffle(rng);
+    let settlement = bundle
+        .world
+        .canonical(bundle.region)
+        .and_then(|region| bundle.world.sim.settlement(region));
+    for entity in &template.entities {
+        let count = match settlement {
+            Some(settlement) => match settlement.count(&entity.template) {
+                Some(count) => count,
+                None => {
+                    let spare = f32::from(entity.max - entity.min) * settlement.supplies;
+                    u32::from(entity.min) + spare.round() as u32
+                }
+            },
+            None => u32::from(rng.gen_range(entity.min, entity.max + 1)),
+        };
+        for _ in 0..count {
+            match spots.pop() {
+                Some(pos) => bundle.spawns.push((entity.template.clone(), pos)),
+                None => return,
+            }
+        }
+    }
+}
@@ -1,6 +1,7 @@
-use super::{util::*, MapGenBundle};
-use crate::component::{Color, Description, Pos};
-use crate::resource::Tile;
+use super::{util::*, water, MapGenBundle};
+use crate::component::{Color, Description, Pos, Region};
+use crate::constants::{MAP_HEIGHT, MAP_WIDTH};
+use crate::resource::{RoadAxis, Tile};
 use crate::util::colors::lerp;
 use crate::util::*;
 
@@ -52,7 +53,21 @@ fn place_car(
         .ok();
 }
 
-fn damaged_road(icon: char, road_bg: Color, ground_bg: Color, blend_factor: f32) -> Tile {
+/// the name of the road running through the region along <axis>
+fn road_name(bundle: &MapGenBundle, axis: RoadAxis) -> String {
+    match bundle.world.road_graph.name_at(bundle.region, axis) {
+        Some(name) => name.to_string(),
+        None => "road".to_string(),
+    }
+}
+
+fn damaged_road(
+    icon: char,
+    road_bg: Color,
+    ground_bg: Color,
+    blend_factor: f32,
+    name: &str,
+) -> Tile {
     let fg = lerp(ground_bg, road_bg, blend_factor * 0.5);
     Tile::new(
         icon,
@@ -61,12 +76,12 @@ fn damaged_road(icon: char, road_bg: Color, ground_bg: Color, blend_factor: f32)
         true,
         true,
         true,
-        Description::new("road", "Little remains of this crumbled asphalt."),
+        Description::new(name, "Little remains of this crumbled asphalt."),
     )
 }
 
 /// creates a single segment of road
-fn road_segment(icon: char, fg: Color, bg: Color) -> Tile {
+fn road_segment(icon: char, fg: Color, bg: Color, name: &str) -> Tile {
     Tile::new(
         icon,
         fg,
@@ -74,7 +89,7 @@ fn road_segment(icon: char, fg: Color, bg: Color) -> Tile {
         true,
         true,
         true,
-        Description::new("road", "A crumbling old road."),
+        Description::new(name, "A crumbling old road."),
     )
 }
 
@@ -99,6 +114,7 @@ pub fn place_horizontal_roads(bundle: &mut MapGenBundle, noise_scale: f32, damag
     let line = bundle.assets.get_icon("road_line_single").ch();
     let dbl = bundle.assets.get_icon("road_line_double").ch();
     let lanes = bundle.world.get_road(bundle.region).lanes_x as usize;
+    let name = road_name(bundle, RoadAxis::Horizontal);
     let offset = bundle.region.to_offset();
 
     let road_rubble = bundle.assets.get_icon("floor_racked").ch();
@@ -122,11 +138,16 @@ pub fn place_horizontal_roads(bundle: &mut MapGenBundle, noise_scale: f32, damag
             ));
             let pos = Pos { x: cx, y: cy };
 
+            if water::is_water(bundle, pos) {
+                bundle.map.try_set(pos, water::bridge(bundle)).ok();
+                continue;
+            }
+
             if i < damage_factor {
                 ground_bg = bundle.map.get(pos).map_or(default_bg, |t| t.bg);
                 bundle
                     .map
-                    .try_set(pos, damaged_road(damage_icon, bg, ground_bg, i))
+                    .try_set(pos, damaged_road(damage_icon, bg, ground_bg, i, &name))
                     .ok();
                 continue;
             }
@@ -164,7 +185,7 @@ pub fn place_horizontal_roads(bundle: &mut MapGenBundle, noise_scale: f32, damag
 
             bundle
                 .map
-                .try_set(pos, road_segment(segment_icon, fg, bg))
+                .try_set(pos, road_segment(segment_icon, fg, bg, &name))
                 .ok();
 
             place_car(bundle, pos, offset, noise_scale, damage_factor);
@@ -194,6 +215,7 @@ pub fn place_vertical_roads(bundle: &mut MapGenBundle, noise_scale: f32, damage_
     let dbl = bundle.assets.get_icon("road_line_double").ch();
     let damage_icon = bundle.assets.get_icon("road_cracked").ch();
     let lanes = bundle.world.get_road(bundle.region).lanes_y as usize;
+    let name = road_name(bundle, RoadAxis::Vertical);
     let offset = bundle.region.to_offset();
 
     let road_rubble = bundle.assets.get_icon("floor_racked").ch();
@@ -215,11 +237,16 @@ pub fn place_vertical_roads(bundle: &mut MapGenBundle, noise_scale: f32, damage_
                 32,
             ));
 
+            if water::is_water(bundle, pos) {
+                bundle.map.try_set(pos, water::bridge(bundle)).ok();
+                continue;
+            }
+
             if i < damage_factor {
                 ground_bg = bundle.map.get(pos).map_or(default_bg, |t| t.bg);
                 bundle
                     .map
-                    .try_set(pos, damaged_road(damage_icon, bg, ground_bg, i))
+                    .try_set(pos, damaged_road(damage_icon, bg, ground_bg, i, &name))
                     .ok();
                 continue;
             }
@@ -257,10 +284,185 @@ pub fn place_vertical_roads(bundle: &mut MapGenBundle, noise_scale: f32, damage_
 
             bundle
                 .map
-                .try_set(pos, road_segment(segment_icon, fg, bg))
+                .try_set(pos, road_segment(segment_icon, fg, bg, &name))
                 .ok();
 
             place_car(bundle, pos, offset, noise_scale, damage_factor);
         }
     }
 }
+
+/// the nearest road curve centerline to a tile, and how far away it is
+#[derive(Copy, Clone)]
+struct CurveDistance {
+    distance: f32,
+    curve: usize,
+}
+
+/// finds the road curves near enough to reach into the region, including those
+/// passing through neighboring regions
+fn nearby_curves(bundle: &MapGenBundle) -> Vec<usize> {
+    let region = bundle.region;
+    let mut indexes: Vec<usize> = Vec::new();
+    for dx in -1..=1 {
+        for dy in -1..=1 {
+            let neighbor = Region::new(region.x + dx, region.y + dy);
+            indexes.extend(bundle.world.curve_indexes(neighbor));
+        }
+    }
+    indexes.sort();
+    indexes.dedup();
+    indexes
+}
+
+/// measures the distance from every tile in the map (plus a margin of one tile
+/// all round) to the nearest curve among <curves>. Tiles too far from any curve
+/// to be part of a road are left empty
+fn curve_distances(bundle: &MapGenBundle, curves: &[usize]) -> Grid<Option<CurveDistance>> {
+    let width = bundle.map.width() as i32;
+    let height = bundle.map.height() as i32;
+    let mut distances: Grid<Option<CurveDistance>> =
+        Grid::with_dimensions(width as usize + 2, height as usize + 2);
+    // curves are laid out in the world, so measure from the region inside it
+    let home = bundle
+        .world
+        .canonical(bundle.region)
+        .unwrap_or(bundle.region);
+    let offset = home.to_offset();
+    let scale = [MAP_WIDTH as f32, MAP_HEIGHT as f32];
+    for index in curves.iter() {
+        let curve = &bundle.world.road_curves[*index];
+        let reach = f32::from(curve.lanes) * 2.0 + 1.0;
+        let steps = (curve.length(scale) * 2.0).ceil().max(1.0) as usize;
+        for step in 0..=steps {
+            let [px, py] = curve.at(step as f32 / steps as f32);
+            let x = px * scale[0] - offset[0] as f32;
+            let y = py * scale[1] - offset[1] as f32;
+            if x < -reach - 1.0
+                || y < -reach - 1.0
+                || x > width as f32 + reach
+                || y > height as f32 + reach
+            {
+                continue;
+            }
+            let min_x = ((x - reach).floor() as i32).max(-1);
+            let max_x = ((x + reach).ceil() as i32).min(width);
+            let min_y = ((y - reach).floor() as i32).max(-1);
+            let max_y = ((y + reach).ceil() as i32).min(height);
+            for ty in min_y..=max_y {
+                for tx in min_x..=max_x {
+                    // tile centers sit half a tile in
+                    let distance = (tx as f32 + 0.5 - x).hypot(ty as f32 + 0.5 - y);
+                    let pos = Pos::new((tx + 1) as usize, (ty + 1) as usize);
+                    let closer = match distances.maybe_get(pos) {
+                        Some(Some(other)) => distance < other.distance,
+                        _ => true,
+                    };
+                    if closer {
+                        distances
+                            .try_set(
+                                pos,
+                                Some(CurveDistance {
+                                    distance,
+                                    curve: *index,
+                                }),
+                            )
+                            .ok();
+                    }
+                }
+            }
+        }
+    }
+    distances
+}
+
+/// draws the road curves crossing the region. Edge and center lines follow the
+/// curve tile by tile, and get joined up when tiles are connected. Damage factor
+/// is a range from 0 = pristine to +1 = completely wrecked.
+pub fn place_curved_roads(bundle: &mut MapGenBundle, noise_scale: f32, damage_factor: f32) {
+    let curves = nearby_curves(bundle);
+    if curves.is_empty() {
+        return;
+    }
+    let road_line_fg = Color::new(102, 92, 81);
+    let road_line_center = Color::new(104, 90, 61);
+    let default_bg = Color::new(4, 4, 4);
+    let bg = default_bg;
+    let road_rubble_fg = Color::new(5, 5, 5);
+
+    let dashed = bundle.assets.get_icon("road_line_dashed").ch();
+    let line = bundle.assets.get_icon("road_line_single").ch();
+    let dbl = bundle.assets.get_icon("road_line_double").ch();
+    let damage_icon = bundle.assets.get_icon("road_cracked").ch();
+    let road_rubble = bundle.assets.get_icon("floor_racked").ch();
+    let offset = bundle.region.to_offset();
+
+    let world = bundle.world;
+    let distances = curve_distances(bundle, &curves);
+    let on_road = |pos: Pos| match distances.maybe_get(pos) {
+        Some(Some(nearest)) => {
+            let lanes = world.road_curves[nearest.curve].lanes;
+            nearest.distance <= f32::from(lanes) * 2.0
+        }
+        _ => false,
+    };
+
+    for pos in bundle.map.bounds().iter() {
+        // distances are stored with a margin of one tile
+        let at = Pos::new(pos.x + 1, pos.y + 1);
+        let nearest = match distances.maybe_get(at) {
+            Some(Some(nearest)) if on_road(at) => *nearest,
+            _ => continue,
+        };
+        let curve = &world.road_curves[nearest.curve];
+        let name = &curve.name;
+        let lanes = curve.lanes;
+
+        if water::is_water(bundle, pos) {
+            bundle.map.try_set(pos, water::bridge(bundle)).ok();
+            continue;
+        }
+
+        let i = rand_up(turb_offset(
+            bundle.noise,
+            pos.to_array(),
+            offset,
+            noise_scale,
+            32,
+        ));
+        if i < damage_factor {
+            let ground_bg = bundle.map.get(pos).map_or(default_bg, |t| t.bg);
+            bundle
+                .map
+                .try_set(pos, damaged_road(damage_icon, bg, ground_bg, i, name))
+                .ok();
+            continue;
+        }
+
+        let edge = !on_road(Pos::new(at.x - 1, at.y))
+            || !on_road(Pos::new(at.x + 1, at.y))
+            || !on_road(Pos::new(at.x, at.y - 1))
+            || !on_road(Pos::new(at.x, at.y + 1));
+        let (mut segment_icon, mut fg) = if edge {
+            (line, road_line_fg)
+        } else if nearest.distance < 0.75 {
+            // larger roads don't have pass lanes
+            (if lanes > 1 { dbl } else { dashed }, road_line_center)
+        } else {
+            (road_rubble, road_rubble_fg)
+        };
+
+        // crossing another road
+        if let Some(true) = bundle.map.get(pos).map(|t| t.constructed) {
+            segment_icon = road_rubble;
+            fg = road_rubble_fg;
+        }
+
+        bundle
+            .map
+            .try_set(pos, road_segment(segment_icon, fg, bg, name))
+            .ok();
+
+        place_car(bundle, pos, offset, noise_scale, damage_factor);
+    }
+}
@@ -1,6 +1,6 @@
-use super::{util::*, MapGenBundle};
+use super::{levels::build_levels, util::*, MapGenBundle};
 use crate::component::Pos;
-use crate::resource::StructureTemplate;
+use crate::resource::{PrefabOrientation, StructureTemplate};
 use crate::util::*;
 use rand::prelude::*;
 
@@ -20,65 +20,169 @@ fn choose_structure<'a>(
     bundle.assets.get_structure(choice)
 }
 
+/// chooses the bounds for a structure starting at <t_l>, and the orientation of
+/// its prefab layout if it has one. None if the structure can't fit there
 fn choose_structure_dimensions(
     sample: f32,
     grid: &Grid<Tile>,
     t_l: Pos,
     structure: &StructureTemplate,
-) -> Rect<usize> {
-    let width_range: Vec<usize> = (structure.min_width..=structure.max_width).collect();
-
-    let height_range: Vec<usize> = (structure.min_height..=structure.max_height).collect();
+) -> Option<(Rect<usize>, PrefabOrientation)> {
+    let mut orientation = PrefabOrientation::default();
+    let (width_range, height_range): (Vec<usize>, Vec<usize>) =
+        if let Some((oriented, (width, height))) = structure.prefab_dimensions(sample) {
+            // prefabs come in fixed sizes
+            orientation = oriented;
+            (vec![width], vec![height])
+        } else {
+            (
+                (structure.min_width..=structure.max_width).collect(),
+                (structure.min_height..=structure.max_height).collect(),
+            )
+        };
     let b_r = Pos::new(
         (choose(&width_range, sample).unwrap_or(0) + t_l.x).min(t_l.x + grid.width() - 1),
         (choose(&height_range, sample).unwrap_or(0) + t_l.y).min(t_l.y + grid.height() - 1),
     );
     let bounds = Rect::new(t_l, b_r);
     if !grid.bounds.contains(bounds) {
-        return Rect::new(Pos::new(0, 0), Pos::new(0, 0));
+        return None;
     }
     /*
     if grid.bounds.width() < MAP_WIDTH && grid.bounds.height() < MAP_HEIGHT {
         dbg!(map_constructed(&grid));
     }
     */
-    grid.fit_rect(bounds, &|tile: &Tile| -> bool { tile.constructed })
+    let bounds = grid.fit_rect(bounds, &|tile: &Tile| -> bool { tile.constructed });
+    // clamped smaller than the structure needs
+    if !structure.fits_in(bounds, orientation) {
+        return None;
+    }
+    Some((bounds, orientation))
 }
 
 use crate::resource::Tile;
+use rand_pcg::Pcg32;
+
+/// how many fallback templates will be tried before settling for a plain floor
+const MAX_FALLBACKS: u8 = 5;
+
+/// Generates the tile labels for a structure's interior with the wfc system. If
+/// the template's rules can't be satisfied in <bounds>, its fallback templates are
+/// tried in turn, and failing those the area is filled with floor. Returns the labels
+/// along with the template they belong to.
+fn generate_labels<'a>(
+    bundle: &'a MapGenBundle,
+    structure: &'a StructureTemplate,
+    bounds: &Rect<usize>,
+    rng: &mut Pcg32,
+) -> (Grid<char>, &'a StructureTemplate) {
+    let mut current = structure;
+    let mut fallbacks = 0;
+    loop {
+        match current.collapse(*bounds, rng) {
+            Ok(labels) => return (labels, current),
+            Err(err) => println!("warning: {}", err),
+        }
+        let next = current
+            .fallback
+            .as_ref()
+            .and_then(|name| bundle.assets.get_structure(name));
+        match next {
+            Some(fallback) if fallbacks < MAX_FALLBACKS => {
+                println!("falling back to structure template '{}'", fallback.name);
+                current = fallback;
+                fallbacks += 1;
+            }
+            _ => {
+                println!(
+                    "falling back to a floor-only fill for structure template '{}'",
+                    structure.name
+                );
+                return (structure.floor_fill(*bounds), structure);
+            }
+        }
+    }
+}
+
+/// stamps a structure's prefab layout into <bounds> in <orientation>, running
+/// the wfc system for any cells marked as fill
+fn stamp_prefab(
+    bundle: &MapGenBundle,
+    structure_grid: &mut Grid<Tile>,
+    bounds: &Rect<usize>,
+    structure: &StructureTemplate,
+    orientation: PrefabOrientation,
+    rng: &mut Pcg32,
+) {
+    let prefab = match structure.prefab() {
+        Some(prefab) => prefab,
+        None => return,
+    };
+    let layout = prefab.oriented(orientation);
+    let filled = match structure.fill {
+        Some(fill) if layout.contains(fill) => {
+            Some(generate_labels(bundle, structure, bounds, rng))
+        }
+        _ => None,
+    };
+    for pos in bounds.iter() {
+        if let Some(label) = layout.get(pos - bounds.t_l) {
+            let mut tile;
+            if structure.is_fill(label) {
+                tile = match &filled {
+                    Some((labels, template)) => template.get_tile(*labels.unchecked_get(pos)),
+                    None => structure.get_tile(label),
+                }
+                .to_tile(bundle.assets);
+                // leave fill areas open for interior structures
+                tile.constructed = false;
+            } else {
+                tile = structure.get_tile(label).to_tile(bundle.assets);
+            }
+            structure_grid.unchecked_set(pos, tile);
+        }
+    }
+}
+
 fn populate_structure(
     bundle: &MapGenBundle,
     structure_grid: &mut Grid<Tile>,
     bounds: &Rect<usize>,
     structure: &StructureTemplate,
+    orientation: PrefabOrientation,
 ) {
-    use wfc::{retry::NumTimes, wrap::WrapNone, RunOwn};
-    let table = structure.get_pattern_table();
-    let stats = wfc::GlobalStats::new(table);
     let rng = &mut bundle.world.region_rng(bundle.region);
-    let wfc_runner = RunOwn::new_wrap(bounds.to_wave_size(), &stats, WrapNone, rng);
-    let wave = wfc_runner
-        .collapse_retrying(NumTimes(1000), rng)
-        .expect("failed to generate structure");
-    let grid = wave.grid();
-    let mapchar = structure.get_mapchar();
-    grid.enumerate().for_each(|(coord, wc)| {
-        let tile = structure.get_tile(mapchar[&wc.chosen_pattern_id().expect("")]);
-        let pos = Pos::from(coord) + bounds.t_l;
+    if structure.prefab().is_some() {
+        stamp_prefab(bundle, structure_grid, bounds, structure, orientation, rng);
+        return;
+    }
+    let (labels, template) = generate_labels(bundle, structure, bounds, rng);
+    for pos in bounds.iter() {
+        let tile = template.get_tile(*labels.unchecked_get(pos));
         structure_grid.unchecked_set(pos, tile.to_tile(bundle.assets))
-    });
+    }
 }
 
-/// Builds structures recursively.
+/// A structure with upper floors or basements, by template name and bounds, and
+/// the orientation its prefab layout was placed in
+pub type Storeys = Vec<(String, Rect<usize>, PrefabOrientation)>;
+
+/// Builds structures recursively, with prefab layouts in <orientation>. Any
+/// structures built that have more than one level are added to <storeys>, so
+/// their other levels can be built afterward.
 /// TODO revisit this, maybe find a way to have fewer parameters, it's kind of
 /// junky passing all this stuff around.
-fn build_structure(
+pub fn build_structure(
     bundle: &MapGenBundle,
     structure: &StructureTemplate,
     mut bounds: Rect<usize>,
+    orientation: PrefabOrientation,
     mut recursions: u8,
+    storeys: &mut Storeys,
 ) -> Option<Grid<Tile>> {
-    if structure.fits_in(bounds) {
+    if structure.fits_in(bounds, orientation) {
+        let outer = bounds;
         recursions += 1;
         if recursions > 10 {
             println!("structure nesting level is too deep");
@@ -88,10 +192,13 @@ fn build_structure(
         let mut grid: Grid<Tile> = Grid::with_bounds(bounds);
         bounds.shrink_perimeter(structure.perimeter);
         // fill in base tiles
-        populate_structure(bundle, &mut grid, &bounds, &structure);
-        // wipe out constructed status so rooms can be built on top
-        for pos in grid.bounds.iter() {
-            grid.unchecked_get_mut(pos).constructed = false;
+        populate_structure(bundle, &mut grid, &bounds, &structure, orientation);
+        // wipe out constructed status so rooms can be built on top (prefabs
+        // only leave their fill areas open)
+        if structure.prefab().is_none() {
+            for pos in grid.bounds.iter() {
+                grid.unchecked_get_mut(pos).constructed = false;
+            }
         }
         let mut room_list = structure.interior_structures.clone();
         let mut remaining_grid = grid.bounds;
@@ -115,9 +222,19 @@ fn build_structure(
                         0.1,
                         1,
                     ));
-                    let bounds =
-                        choose_structure_dimensions(sample, &grid, remaining_grid.t_l, &room);
-                    if let Some(room_grid) = build_structure(bundle, room, bounds, recursions) {
+                    let room_grid =
+                        choose_structure_dimensions(sample, &grid, remaining_grid.t_l, &room)
+                            .and_then(|(bounds, orientation)| {
+                                build_structure(
+                                    bundle,
+                                    room,
+                                    bounds,
+                                    orientation,
+                                    recursions,
+                                    storeys,
+                                )
+                            });
+                    if let Some(room_grid) = room_grid {
                         built += 1;
                         grid.paste_into(Pos::new(0, 0), room_grid).ok()?;
                     }
@@ -145,6 +262,9 @@ fn build_structure(
         for pos in bounds.iter() {
             grid.unchecked_get_mut(pos).constructed = true;
         }
+        if !structure.extra_levels().is_empty() {
+            storeys.push((structure.name.clone(), outer, orientation));
+        }
         return Some(grid);
     }
     None
@@ -161,6 +281,49 @@ pub fn map_constructed(grid: &Grid<Tile>) -> Grid<bool> {
     c_grid
 }
 
+/// builds <structure> into the map with its top left corner at <top_left>, along
+/// with any other levels it has. Returns the structure's bounds if it fit
+pub fn place_structure(
+    bundle: &mut MapGenBundle,
+    structure: &StructureTemplate,
+    top_left: Pos,
+) -> Result<Option<Rect<usize>>, &'static str> {
+    let sample = rand_up(fbm_offset(
+        bundle.noise,
+        top_left.to_array(),
+        bundle.region.to_offset(),
+        0.1,
+        1,
+    ));
+    // nothing is placed when the structure doesn't fit here
+    let (bounds, orientation) =
+        match choose_structure_dimensions(sample, &bundle.map.grid, top_left, structure) {
+            Some(chosen) => chosen,
+            None => return Ok(None),
+        };
+    let mut storeys = Storeys::new();
+    let structure_grid =
+        match build_structure(bundle, structure, bounds, orientation, 0, &mut storeys) {
+            Some(grid) => grid,
+            None => return Ok(None),
+        };
+    bundle.map.paste_into(Default::default(), structure_grid)?;
+    let assets = bundle.assets;
+    for (name, bounds, orientation) in storeys {
+        if let Some(template) = assets.get_structure(&name) {
+            build_levels(bundle, template, bounds, orientation)?;
+        }
+    }
+    let mut perimeter = bounds;
+    perimeter.expand_perimeter(1);
+    for pos in perimeter.iter_perimeter() {
+        if let Some(tile) = bundle.map.grid.maybe_get_mut(pos) {
+            tile.constructed = true;
+        }
+    }
+    Ok(Some(bounds))
+}
+
 pub fn build(bundle: &mut MapGenBundle) -> Result<bool, &'static str> {
     let mut count: u8 = 0;
     // roughly 1.5 slots per .1 pop
@@ -198,24 +361,7 @@ pub fn build(bundle: &mut MapGenBundle) -> Result<bool, &'static str> {
         let structure = &choose_structure(bundle, top_left, structures)
             .unwrap()
             .clone();
-        let sample = rand_up(fbm_offset(
-            bundle.noise,
-            top_left.to_array(),
-            region.to_offset(),
-            0.1,
-            1,
-        ));
-        let mut bounds =
-            choose_structure_dimensions(sample, &bundle.map.grid, top_left, &structure);
-        let maybe_grid = build_structure(bundle, structure, bounds, 0);
-        if let Some(structure_grid) = maybe_grid {
-            bundle.map.paste_into(Default::default(), structure_grid)?;
-            bounds.expand_perimeter(1);
-            for pos in bounds.iter_perimeter() {
-                if let Some(tile) = bundle.map.grid.maybe_get_mut(pos) {
-                    tile.constructed = true;
-                }
-            }
+        if place_structure(bundle, structure, top_left)?.is_some() {
             count += structure.building_slots;
         }
     }
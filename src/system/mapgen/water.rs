@@ -0,0 +1,230 @@
+//! Seas and lakes, the shorelines bordering them, and rivers running between
+//! regions. Water is worked out per tile from the world's hydrology map, so other
+//! generators (roads, for bridges) can ask whether a tile is under water.
+use super::{util::*, MapGenBundle};
+use crate::component::{Color, Description, Direction, Pos, Region};
+use crate::resource::{Hydrology, Tile};
+use crate::util::rand_up;
+
+/// the shallowest a shoreline reaches into a coastal region, in tiles
+const SHORE_MIN: f32 = 3.0;
+/// the deepest a shoreline reaches into a coastal region, in tiles
+const SHORE_MAX: f32 = 16.0;
+/// half the width of a river channel, in tiles
+const RIVER_HALF_WIDTH: f32 = 2.5;
+/// how far a river wanders from a straight course, in tiles
+const RIVER_MEANDER: f32 = 8.0;
+
+const DIRECTIONS: [Direction; 4] = [
+    Direction::North,
+    Direction::South,
+    Direction::East,
+    Direction::West,
+];
+
+fn opposite(dir: Direction) -> Direction {
+    match dir {
+        Direction::North => Direction::South,
+        Direction::South => Direction::North,
+        Direction::East => Direction::West,
+        Direction::West => Direction::East,
+    }
+}
+
+fn neighbor(region: Region, dir: Direction) -> Region {
+    match dir {
+        Direction::North => Region::new(region.x, region.y - 1),
+        Direction::South => Region::new(region.x, region.y + 1),
+        Direction::East => Region::new(region.x + 1, region.y),
+        Direction::West => Region::new(region.x - 1, region.y),
+    }
+}
+
+fn neighbor_water(bundle: &MapGenBundle, dir: Direction) -> Option<(Hydrology, Option<Direction>)> {
+    let region = neighbor(bundle.region, dir);
+    if bundle.world.contains(region) {
+        let water = bundle.world.get_water(region);
+        Some((water.kind, water.flows_to))
+    } else {
+        None
+    }
+}
+
+/// distance in tiles from <pos> to the edge of the map facing <dir>
+fn edge_distance(bundle: &MapGenBundle, pos: Pos, dir: Direction) -> usize {
+    match dir {
+        Direction::North => pos.y,
+        Direction::South => bundle.map.height() - 1 - pos.y,
+        Direction::East => bundle.map.width() - 1 - pos.x,
+        Direction::West => pos.x,
+    }
+}
+
+/// checks whether <pos> lies within the shoreline of a neighboring lake or sea
+fn in_shore(bundle: &MapGenBundle, pos: Pos) -> bool {
+    let [offset_x, offset_y] = bundle.region.to_offset();
+    DIRECTIONS.iter().any(|dir| {
+        match neighbor_water(bundle, *dir) {
+            Some((kind, _)) if kind.is_body() => {}
+            _ => return false,
+        }
+        // sample along the edge in world coordinates, so shores line up between
+        // neighboring regions
+        let along = match dir {
+            Direction::North | Direction::South => [pos.x as i32, offset_x],
+            Direction::East | Direction::West => [pos.y as i32, offset_y],
+        };
+        let sample = rand_up(fbm_offset(
+            bundle.noise,
+            [along[0], 0],
+            [along[1], 0],
+            0.05,
+            2,
+        ));
+        let depth = SHORE_MIN + sample * (SHORE_MAX - SHORE_MIN);
+        (edge_distance(bundle, pos, *dir) as f32) < depth
+    })
+}
+
+/// the directions a river leaves the region in: downstream, and toward every
+/// upstream neighbor that flows into this region
+fn river_arms(bundle: &MapGenBundle) -> Vec<Direction> {
+    let water = bundle.world.get_water(bundle.region);
+    DIRECTIONS
+        .iter()
+        .filter(|dir| {
+            water.flows_to == Some(**dir)
+                || match neighbor_water(bundle, **dir) {
+                    Some((Hydrology::River, Some(flow))) => flow == opposite(**dir),
+                    _ => false,
+                }
+        })
+        .cloned()
+        .collect()
+}
+
+/// the point where a river crosses the edge of the map facing <dir>. Always the
+/// middle of the edge, so rivers meet up between regions
+fn crossing(bundle: &MapGenBundle, dir: Direction) -> (f32, f32) {
+    let w = bundle.map.width() as f32;
+    let h = bundle.map.height() as f32;
+    match dir {
+        Direction::North => (w / 2.0, 0.0),
+        Direction::South => (w / 2.0, h - 1.0),
+        Direction::East => (w - 1.0, h / 2.0),
+        Direction::West => (0.0, h / 2.0),
+    }
+}
+
+/// where the arms of a river meet, wandering a little from the middle of the map
+fn river_center(bundle: &MapGenBundle) -> (f32, f32) {
+    let offset = bundle.region.to_offset();
+    let dx = fbm_offset(bundle.noise, [0, 0], offset, 1.0, 1) * RIVER_MEANDER;
+    let dy = fbm_offset(bundle.noise, [1, 1], offset, 1.0, 1) * RIVER_MEANDER;
+    (
+        bundle.map.width() as f32 / 2.0 + dx,
+        bundle.map.height() as f32 / 2.0 + dy,
+    )
+}
+
+/// checks whether <pos> lies in the arm of the river running from <center>
+/// toward the edge facing <dir>
+fn in_river_arm(bundle: &MapGenBundle, pos: Pos, dir: Direction, center: (f32, f32)) -> bool {
+    let (edge_x, edge_y) = crossing(bundle, dir);
+    let (cx, cy) = center;
+    let offset = bundle.region.to_offset();
+    // work along the arm's length, measuring across its width
+    let (along, across, start, end, from, to) = match dir {
+        Direction::East | Direction::West => (pos.x, pos.y, cx, edge_x, cy, edge_y),
+        Direction::North | Direction::South => (pos.y, pos.x, cy, edge_y, cx, edge_x),
+    };
+    let along_f = along as f32;
+    if along_f < start.min(end) || along_f > start.max(end) {
+        return false;
+    }
+    let t = if (end - start).abs() < 1.0 {
+        1.0
+    } else {
+        (along_f - start) / (end - start)
+    };
+    // meander most in the middle of the arm, and not at all where it meets
+    // the center or the edge
+    let wobble = fbm_offset(bundle.noise, [along as i32, 0], offset, 0.05, 2)
+        * RIVER_MEANDER
+        * 4.0
+        * t
+        * (1.0 - t);
+    let course = from + (to - from) * t + wobble;
+    (across as f32 - course).abs() <= RIVER_HALF_WIDTH
+}
+
+fn in_river(bundle: &MapGenBundle, pos: Pos) -> bool {
+    let center = river_center(bundle);
+    river_arms(bundle)
+        .iter()
+        .any(|dir| in_river_arm(bundle, pos, *dir, center))
+}
+
+/// checks whether the tile at <pos> is under water
+pub fn is_water(bundle: &MapGenBundle, pos: Pos) -> bool {
+    let water = bundle.world.get_water(bundle.region);
+    if water.kind.is_body() {
+        return true;
+    }
+    in_shore(bundle, pos) || (water.kind == Hydrology::River && in_river(bundle, pos))
+}
+
+fn water_tile(bundle: &MapGenBundle, deep: bool) -> Tile {
+    let (bg, description) = if deep {
+        (
+            Color::new(8, 20, 48),
+            Description::new(
+                "deep water",
+                "Cold, dark water. There's no telling how deep.",
+            ),
+        )
+    } else {
+        (
+            Color::new(16, 36, 64),
+            Description::new("water", "Murky water, too deep to wade through."),
+        )
+    };
+    Tile {
+        icon: bundle.assets.get_icon("water").ch(),
+        fg: Color::new(40, 70, 110),
+        bg,
+        walkable: false,
+        constructed: true,
+        description,
+        ..Tile::default()
+    }
+}
+
+/// a bridge tile, for roads crossing water
+pub fn bridge(bundle: &MapGenBundle) -> Tile {
+    Tile {
+        icon: bundle.assets.get_icon("bridge").ch(),
+        fg: Color::new(110, 96, 80),
+        bg: Color::new(28, 24, 20),
+        constructed: true,
+        description: Description::new("bridge", "A sagging old bridge over the water."),
+        ..Tile::default()
+    }
+}
+
+/// draws all the water in the region
+pub fn place(bundle: &mut MapGenBundle) {
+    let water = bundle.world.get_water(bundle.region);
+    if water.kind == Hydrology::Dry {
+        return;
+    }
+    let deep = water_tile(bundle, water.kind == Hydrology::Sea);
+    let shallow = water_tile(bundle, false);
+    for pos in bundle.map.bounds().iter() {
+        if water.kind.is_body() {
+            bundle.map.unchecked_set(pos, deep.clone());
+        } else if is_water(bundle, pos) {
+            bundle.map.unchecked_set(pos, shallow.clone());
+        }
+    }
+}
@@ -2,24 +2,40 @@ mod ai;
 mod assets;
 mod bump_interact;
 mod collision_system;
+mod dialogue;
 mod interact;
 mod mapgen;
 mod movement;
+mod persist;
+mod quests;
 mod region;
+mod save;
 mod stage;
 mod tick;
+mod travel;
+mod ui_actions;
 mod world_gen;
+mod world_sim;
 
 pub mod input;
 
 pub use ai::AI;
 pub use assets::AssetLoader;
+#[cfg(test)]
+pub use assets::load_assets;
 pub use bump_interact::BumpInteract;
 pub use collision_system::CollisionSystem;
-pub use interact::Notify;
+pub use dialogue::Talk;
+pub use interact::{Attack, Examine, Notify, Open, PickUp, Search, VerbMenu};
 pub use mapgen::*;
 pub use movement::Movement;
+pub use persist::StoreEntities;
+pub use quests::Quests;
 pub use region::RegionSystem;
+pub use save::SaveGame;
 pub use stage::*;
 pub use tick::{PostTick, PreTick};
+pub use travel::AutoTravel;
+pub use ui_actions::{ShowNotifications, UIActions};
 pub use world_gen::WorldGen;
+pub use world_sim::SimulateWorld;
@@ -1,19 +1,67 @@
 use crate::component::*;
 use crate::constants::{MAP_HEIGHT, MAP_WIDTH};
-use crate::resource::{RegionMaps, CollisionMaps};
+use crate::resource::{CollisionMaps, GameEvent, GameEvents, RegionMap, RegionMaps};
 use crate::util::clamp;
 use specs::{Entities, Join, Read, ReadStorage, System, Write, WriteStorage};
 
+/// moves along <plan>, returning the new position if nothing was in the way
+fn try_move(
+    plan: MovePlan,
+    pos: Pos,
+    region: Region,
+    solid: bool,
+    map: &RegionMap,
+    collision_maps: &CollisionMaps,
+) -> Option<Pos> {
+    let new_pos = Pos {
+        x: clamp(0, MAP_WIDTH as i32 - 1, plan.x + pos.x as i32) as usize,
+        y: clamp(0, MAP_HEIGHT as i32 - 1, plan.y + pos.y as i32) as usize,
+    };
+    // only solid entities care about collisions
+    if solid
+        && (!map.get(new_pos).map_or(false, |t| t.walkable) || collision_maps.get(region, new_pos))
+    {
+        return None;
+    }
+    Some(new_pos)
+}
+
+/// falls back to walking when too out of breath to keep up <mode>, returning
+/// whether it did
+fn catch_breath(mode: &mut MovementMode, character: &Character) -> bool {
+    let cost = mode.stamina_cost();
+    // never run yourself into the ground
+    if cost > 0 && character.stamina().1 <= cost {
+        *mode = MovementMode::Walk;
+        return true;
+    }
+    false
+}
+
+/// pays for a move in <mode>. Easy paces recover stamina instead
+fn pay_stamina(mode: MovementMode, character: &mut Character) {
+    match mode.stamina_cost() {
+        0 => character.recover_stamina(1),
+        cost => {
+            character.spend_stamina(cost);
+        }
+    }
+}
+
 pub struct Movement;
 impl<'a> System<'a> for Movement {
     type SystemData = (
         ReadStorage<'a, Region>,
         WriteStorage<'a, MovePlan>,
+        WriteStorage<'a, MovementMode>,
+        WriteStorage<'a, Character>,
         ReadStorage<'a, Solid>,
+        ReadStorage<'a, Player>,
         WriteStorage<'a, Orientation>,
         WriteStorage<'a, Pos>,
         Read<'a, RegionMaps>,
         Write<'a, CollisionMaps>,
+        Write<'a, GameEvents>,
         Entities<'a>,
     );
 
@@ -22,19 +70,26 @@ impl<'a> System<'a> for Movement {
         (
             regions,
             mut plans,
+            mut modes,
+            mut characters,
             solids,
+            players,
             mut orientations,
             mut positions,
             area_maps,
             mut collision_maps,
+            mut events,
             entities,
         ): Self::SystemData,
     ) {
-        for (region, orientation, plan, pos, entity) in (
+        // NPCs spawned from templates don't have an orientation to keep track of
+        for (region, mut orientation, plan, pos, mut mode, character, entity) in (
             &regions,
-            &mut orientations,
+            (&mut orientations).maybe(),
             &mut plans,
             &mut positions,
+            (&mut modes).maybe(),
+            (&mut characters).maybe(),
             &entities,
         )
             .join()
@@ -43,45 +98,61 @@ impl<'a> System<'a> for Movement {
             if !area_maps.has(*region) {
                 continue;
             }
+            // standing still isn't a move, and doesn't cost or recover anything
+            if plan.x == 0 && plan.y == 0 {
+                continue;
+            }
             let map = area_maps.get(*region);
-            let new_pos = Pos {
-                x: clamp(0, MAP_WIDTH as i32 - 1, plan.x + pos.x as i32) as usize,
-                y: clamp(0, MAP_HEIGHT as i32 - 1, plan.y + pos.y as i32) as usize,
-            };
-            let mut ok: bool;
-            // only solid entities care about collisions, so let's check if it's solid
-            let solid: Option<&Solid> = solids.get(entity);
-            match solid {
-                Some(_) => {
-                    // got a solid, so run collision checks
-                    ok = map.get(new_pos).map_or(false, |t| t.walkable);
-                    // if we're still ok, check if there's a colliding object
-                    if ok {
-                        ok = !collision_maps.get(*region, new_pos);
+            let solid = solids.get(entity).is_some();
+            // actors with a movement mode go one checked tile at a time, as many
+            // tiles as the mode allows, so running can't skip through walls
+            let (steps, unit) = match mode.as_mut() {
+                Some(mode) => {
+                    let pace = mode.name();
+                    let winded = character.as_ref().map(|c| catch_breath(mode, c));
+                    if winded == Some(true) && players.get(entity).is_some() {
+                        events.single_write(GameEvent::Notified {
+                            title: "Winded".to_string(),
+                            body: format!("Too winded to keep {}, you slow to a walk.", pace),
+                        });
                     }
+                    let unit = MovePlan {
+                        x: plan.x.signum(),
+                        y: plan.y.signum(),
+                    };
+                    (mode.steps(), unit)
                 }
-                None => {
-                    ok = true;
-                } // always ok to move if not solid
-            }
-            if ok {
-                if plan.x > 0 {
-                    orientation.dir = Direction::East;
-                }
-                if plan.x < 0 {
-                    orientation.dir = Direction::West;
-                }
-                if plan.y > 0 {
-                    orientation.dir = Direction::South;
-                }
-                if plan.y < 0 {
-                    orientation.dir = Direction::North;
+                None => (1, *plan),
+            };
+            let mut moved = false;
+            for _ in 0..steps {
+                let new_pos = match try_move(unit, *pos, *region, solid, map, &collision_maps) {
+                    Some(new_pos) if new_pos != *pos => new_pos,
+                    _ => break,
+                };
+                if let Some(orientation) = orientation.as_mut() {
+                    if unit.x > 0 {
+                        orientation.dir = Direction::East;
+                    }
+                    if unit.x < 0 {
+                        orientation.dir = Direction::West;
+                    }
+                    if unit.y > 0 {
+                        orientation.dir = Direction::South;
+                    }
+                    if unit.y < 0 {
+                        orientation.dir = Direction::North;
+                    }
                 }
-                // do the move if all checks passed
                 collision_maps.set(*region, *pos, false);
                 collision_maps.set(*region, new_pos, true);
-                pos.x = new_pos.x;
-                pos.y = new_pos.y;
+                events.single_write(GameEvent::Moved { entity });
+                *pos = new_pos;
+                moved = true;
+            }
+            // only a step actually taken costs anything
+            if let (true, Some(mode), Some(character)) = (moved, mode, character) {
+                pay_stamina(*mode, character);
             }
             plan.x = 0;
             plan.y = 0;
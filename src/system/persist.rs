@@ -0,0 +1,74 @@
+/// keeps entities around when their region is unloaded, so they can be put back
+/// the way they were when it loads again
+use crate::component::{AIBrain, Character, Container, IconRef, Player, Pos, Region, Template};
+use crate::resource::{EntityStore, GameStage, GameState, RegionMaps, StoredEntity, WorldState};
+use specs::{Entities, Join, Read, ReadStorage, System, Write};
+
+pub struct StoreEntities;
+impl<'a> System<'a> for StoreEntities {
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, Player>,
+        ReadStorage<'a, IconRef>,
+        ReadStorage<'a, Template>,
+        ReadStorage<'a, Pos>,
+        ReadStorage<'a, Region>,
+        ReadStorage<'a, Character>,
+        ReadStorage<'a, AIBrain>,
+        ReadStorage<'a, Container>,
+        Read<'a, RegionMaps>,
+        Read<'a, GameState>,
+        Read<'a, WorldState>,
+        Write<'a, EntityStore>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            players,
+            icons,
+            templates,
+            positions,
+            regions,
+            characters,
+            brains,
+            containers,
+            maps,
+            state,
+            world,
+            mut store,
+        ): Self::SystemData,
+    ) {
+        // loading a game clears out the old world itself
+        if state.stage != GameStage::Playing {
+            return;
+        }
+        for (entity, _, pos, region, template, ..) in (
+            &entities,
+            &icons,
+            &positions,
+            &regions,
+            templates.maybe(),
+            !&players,
+        )
+            .join()
+        {
+            if maps.has(*region) {
+                continue;
+            }
+            // anything not spawned from a template can't be rebuilt, so it just goes
+            if let Some(template) = template {
+                store.store(StoredEntity::capture(
+                    template,
+                    *pos,
+                    world.wrap(*region),
+                    characters.get(entity),
+                    brains.get(entity),
+                    containers.get(entity),
+                ));
+            }
+            entities.delete(entity).expect("failed to delete entity");
+        }
+    }
+}
@@ -0,0 +1,105 @@
+/// tracks the player's progress through their quests, handing out rewards when
+/// they're finished
+use super::ui_actions::apply_effect;
+use crate::component::{Inventory, Player, Region};
+use crate::resource::{
+    subscribe, Assets, GameEvent, GameEvents, GameStage, GameState, QuestEvent, QuestSituation,
+    QuestUpdate, WorldState,
+};
+use specs::{
+    Join, Read, ReadStorage, ReaderId, Resources, System, SystemData, Write, WriteStorage,
+};
+
+#[derive(Default)]
+pub struct Quests {
+    reader: Option<ReaderId<GameEvent>>,
+    /// how many quests the log held last time, so newly started ones get checked
+    /// against where the player already is
+    known: usize,
+}
+
+impl<'a> System<'a> for Quests {
+    type SystemData = (
+        ReadStorage<'a, Player>,
+        ReadStorage<'a, Region>,
+        WriteStorage<'a, Inventory>,
+        Write<'a, GameEvents>,
+        Write<'a, WorldState>,
+        Read<'a, Assets>,
+        Read<'a, GameState>,
+    );
+
+    fn setup(&mut self, res: &mut Resources) {
+        Self::SystemData::setup(res);
+        self.reader = Some(subscribe(res));
+    }
+
+    fn run(
+        &mut self,
+        (players, regions, mut inventories, mut events, mut world, assets, state): Self::SystemData,
+    ) {
+        // moving and picking things up change the player's situation, without
+        // counting toward anything themselves
+        let mut moved = false;
+        let happened: Vec<QuestEvent> = match &mut self.reader {
+            Some(reader) => events
+                .read(reader)
+                .filter_map(|event| match event {
+                    GameEvent::Killed {
+                        template: Some(template),
+                    } => Some(QuestEvent::Killed(template.clone())),
+                    GameEvent::Talked(dialogue) => Some(QuestEvent::Talked(dialogue.clone())),
+                    GameEvent::RegionChanged | GameEvent::PickedUp => {
+                        moved = true;
+                        None
+                    }
+                    _ => None,
+                })
+                .collect(),
+            None => return,
+        };
+        if state.stage != GameStage::Playing || !world.ready {
+            return;
+        }
+        let started = world.quests.quests().len() != self.known;
+        self.known = world.quests.quests().len();
+        // nothing has changed since the quests were last checked
+        if happened.is_empty() && !moved && !started {
+            return;
+        }
+        let (region, inventory) = match (&players, &regions, &mut inventories).join().next() {
+            Some((_, region, inventory)) => (*region, inventory),
+            None => return,
+        };
+        let situation = QuestSituation {
+            region,
+            poi: world
+                .get_point_of_interest(region)
+                .map(|poi| poi.template.clone()),
+            items: inventory.items.clone(),
+        };
+        for update in world.quests.update(&assets, &happened, &situation) {
+            match update {
+                QuestUpdate::Advanced(quest) => {
+                    let template = assets.get_quest(&quest).unwrap();
+                    let progress = world.quests.get(&quest).unwrap();
+                    events.single_write(GameEvent::Notified {
+                        title: format!("Quest updated: {}", template.title),
+                        body: template.stages[progress.stage].description.clone(),
+                    });
+                }
+                QuestUpdate::Finished(quest) => {
+                    let template = assets.get_quest(&quest).unwrap();
+                    events.single_write(GameEvent::Notified {
+                        title: format!("Quest complete: {}", template.title),
+                        body: format!("You have finished \"{}\".", template.title),
+                    });
+                    for reward in &template.rewards {
+                        let inventory = Some(&mut *inventory);
+                        apply_effect(reward, &mut world, &assets, inventory, &mut events);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,20 +1,22 @@
 use crate::component::*;
 use crate::constants::CHUNK_RADIUS;
-use crate::resource::{RegionMaps, CollisionMaps, GameState};
-use specs::{Entities, Join, ReadStorage, System, Write, WriteStorage};
+use crate::resource::{CollisionMaps, GameEvent, GameEvents, GameState, RegionMaps, WorldState};
+use specs::{Entities, Join, Read, ReadStorage, System, Write, WriteStorage};
 
 pub struct RegionSystem;
 
 impl<'a> System<'a> for RegionSystem {
     type SystemData = (
         ReadStorage<'a, Player>,
-        ReadStorage<'a, IconRef>,
+        ReadStorage<'a, Solid>,
         WriteStorage<'a, MovePlan>,
         WriteStorage<'a, Pos>,
         WriteStorage<'a, Region>,
         Write<'a, CollisionMaps>,
         Write<'a, RegionMaps>,
         Write<'a, GameState>,
+        Write<'a, GameEvents>,
+        Read<'a, WorldState>,
         Entities<'a>,
     );
 
@@ -22,17 +24,18 @@ impl<'a> System<'a> for RegionSystem {
         &mut self,
         (
             players,
-            icons,
+            solids,
             mut plans,
             mut positions,
             mut regions,
             mut collision_maps,
             mut maps,
             mut state,
+            mut events,
+            world,
             entities,
         ): Self::SystemData,
     ) {
-        let mut player_changed_region = false;
         for (mut region, plan, pos, entity) in
             (&mut regions, &mut plans, &mut positions, &entities).join()
         {
@@ -45,7 +48,11 @@ impl<'a> System<'a> for RegionSystem {
                     is_player = false;
                 }
             }
-            let map = maps.get(state.region);
+            // guard against entities outside currently loaded map
+            if !maps.has(*region) {
+                continue;
+            }
+            let map = maps.get(*region);
             let mut change_x: i32 = 0;
             let mut change_y: i32 = 0;
             if plan.x != 0 || plan.y != 0 {
@@ -54,31 +61,77 @@ impl<'a> System<'a> for RegionSystem {
                     x: plan.x + ipos.x,
                     y: plan.y + ipos.y,
                 };
+                let off_map = target.x < 0
+                    || target.y < 0
+                    || target.x >= map.width() as i32
+                    || target.y >= map.height() as i32;
+                if off_map && region.z != 0 {
+                    // upper floors and basements don't connect to their neighbors
+                    plan.x = 0;
+                    plan.y = 0;
+                    continue;
+                }
                 if target.x >= map.width() as i32 {
                     change_x = 1;
-                    pos.x = 0;
                 }
                 if target.x < 0 {
                     change_x = -1;
-                    pos.x = map.width() - 1;
                 }
                 if target.y >= map.height() as i32 {
                     change_y = 1;
-                    pos.y = 0;
                 }
                 if target.y < 0 {
                     change_y = -1;
-                    pos.y = map.height() - 1;
+                }
+                let next = Region::new(region.x + change_x, region.y + change_y);
+                if off_map && !world.contains(next) {
+                    // can't leave a bordered world
+                    if is_player {
+                        events.single_write(GameEvent::Notified {
+                            title: "The edge of the world".to_string(),
+                            body: "There's nothing that way but wasteland.".to_string(),
+                        });
+                    }
+                    plan.x = 0;
+                    plan.y = 0;
+                    continue;
+                }
+                let mut arrival = *pos;
+                match change_x {
+                    1 => arrival.x = 0,
+                    -1 => arrival.x = map.width() - 1,
+                    _ => {}
+                }
+                match change_y {
+                    1 => arrival.y = 0,
+                    -1 => arrival.y = map.height() - 1,
+                    _ => {}
+                }
+                let solid = solids.get(entity).is_some();
+                // NPCs only wander into neighbors that are loaded, and have room
+                let blocked = !is_player
+                    && (!maps.ready(next)
+                        || maps.get_tile(next, arrival).map(|t| t.walkable) != Some(true)
+                        || (solid && collision_maps.get(next, arrival)));
+                if off_map && blocked {
+                    plan.x = 0;
+                    plan.y = 0;
+                    continue;
                 }
                 if change_x != 0 || change_y != 0 {
+                    if solid {
+                        collision_maps.set(*region, *pos, false);
+                        collision_maps.set(next, arrival, true);
+                    }
+                    *pos = arrival;
                     if is_player {
                         println!("changing region to {}, {}", change_x, change_y);
                         state.change_region(change_x, change_y);
+                        events.single_write(GameEvent::RegionChanged);
                         maps.init(state.region, CHUNK_RADIUS);
                         maps.prune(state.region, CHUNK_RADIUS);
                         collision_maps.init(state.region, CHUNK_RADIUS);
                         collision_maps.prune(state.region, CHUNK_RADIUS);
-                        player_changed_region = true;
                     }
                     region.x += change_x;
                     region.y += change_y;
@@ -86,14 +139,6 @@ impl<'a> System<'a> for RegionSystem {
                     plan.y = 0;
                 }
             }
-        } // end entity region change loop
-          // prune entities outside the currently loaded regions
-        if player_changed_region {
-            for (region, _icon, entity) in (&regions, &icons, &entities).join() {
-                if !maps.has(*region) {
-                    entities.delete(entity).expect("failed to delete entity");
-                }
-            }
         }
     }
 }
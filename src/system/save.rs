@@ -0,0 +1,220 @@
+/// saves the game to disk and loads it back
+use crate::component::{AIBrain, Character, Container, Inventory, Player, Pos, Region, Template};
+use crate::constants::{CHUNK_RADIUS, SAVE_FILE};
+use crate::resource::{
+    CollisionMaps, EntityStore, Explored, GameEvent, GameEvents, GameStage, GameState, RegionMaps,
+    StoredEntity, UserInput, WorldState,
+};
+use serde::{Deserialize, Serialize};
+use specs::{Entities, Join, ReadStorage, System, Write, WriteStorage};
+use std::fs::{create_dir_all, read_to_string, write};
+use std::path::Path;
+use tcod::input::Key;
+use tcod::input::KeyCode::*;
+
+/// the parts of the player worth keeping. Everything else is rebuilt on load
+#[derive(Clone, Default, Deserialize, Serialize)]
+struct SavedPlayer {
+    pos: Pos,
+    region: Region,
+    character: Character,
+    inventory: Inventory,
+}
+
+/// everything written to the save file. The world's map layers are skipped, since
+/// they're regenerated from its seed. Entities in loaded regions are saved along
+/// with the ones stored away
+#[derive(Deserialize, Serialize)]
+struct SaveFile {
+    world: WorldState,
+    player: SavedPlayer,
+    #[serde(default)]
+    explored: Explored,
+    #[serde(default)]
+    entities: EntityStore,
+}
+
+fn save(file: &SaveFile) -> Result<(), &'static str> {
+    let text = ron::ser::to_string_pretty(file, ron::ser::PrettyConfig::default())
+        .map_err(|_| "could not serialize the game")?;
+    if let Some(dir) = Path::new(SAVE_FILE).parent() {
+        create_dir_all(dir).map_err(|_| "could not create the save directory")?;
+    }
+    write(SAVE_FILE, text).map_err(|_| "could not write the save file")
+}
+
+fn load() -> Result<SaveFile, &'static str> {
+    let text = read_to_string(SAVE_FILE).map_err(|_| "could not read the save file")?;
+    ron::de::from_str(&text).map_err(|_| "could not parse the save file")
+}
+
+/// saves the game on [F5] and loads it on [F6]
+pub struct SaveGame;
+impl<'a> System<'a> for SaveGame {
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, Player>,
+        ReadStorage<'a, Template>,
+        ReadStorage<'a, AIBrain>,
+        ReadStorage<'a, Container>,
+        WriteStorage<'a, Pos>,
+        WriteStorage<'a, Region>,
+        WriteStorage<'a, Character>,
+        WriteStorage<'a, Inventory>,
+        Write<'a, UserInput>,
+        Write<'a, RegionMaps>,
+        Write<'a, CollisionMaps>,
+        Write<'a, GameState>,
+        Write<'a, WorldState>,
+        Write<'a, Explored>,
+        Write<'a, EntityStore>,
+        Write<'a, GameEvents>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            players,
+            templates,
+            brains,
+            containers,
+            mut positions,
+            mut regions,
+            mut characters,
+            mut inventories,
+            mut input,
+            mut maps,
+            mut collision_maps,
+            mut state,
+            mut world,
+            mut explored,
+            mut store,
+            mut events,
+        ): Self::SystemData,
+    ) {
+        match input.get() {
+            Some(Key { code: F5, .. }) => {
+                input.consume();
+                if state.stage != GameStage::Playing {
+                    return;
+                }
+                let player = (&players, &positions, &regions, &characters, &inventories)
+                    .join()
+                    .next()
+                    .map(|(_, pos, region, character, inventory)| SavedPlayer {
+                        pos: *pos,
+                        region: *region,
+                        character: *character,
+                        inventory: inventory.clone(),
+                    })
+                    .unwrap_or_default();
+                let mut saved = store.clone();
+                for (entity, template, pos, region, _) in
+                    (&entities, &templates, &positions, &regions, !&players).join()
+                {
+                    saved.store(StoredEntity::capture(
+                        template,
+                        *pos,
+                        world.wrap(*region),
+                        characters.get(entity),
+                        brains.get(entity),
+                        containers.get(entity),
+                    ));
+                }
+                let file = SaveFile {
+                    world: world.clone(),
+                    player,
+                    explored: explored.clone(),
+                    entities: saved,
+                };
+                let body = match save(&file) {
+                    Ok(()) => "Your progress has been saved.".to_string(),
+                    Err(err) => format!("Saving failed: {}.", err),
+                };
+                events.single_write(GameEvent::Notified {
+                    title: "Save".to_string(),
+                    body,
+                });
+            }
+            Some(Key { code: F6, .. }) => {
+                input.consume();
+                // the world can't be rebuilt until the assets are in, and the
+                // player may load their way out of death
+                if state.stage != GameStage::Playing && state.stage != GameStage::Dead {
+                    return;
+                }
+                let file = match load() {
+                    Ok(file) => file,
+                    Err(err) => {
+                        events.single_write(GameEvent::Notified {
+                            title: "Load".to_string(),
+                            body: format!("Loading failed: {}.", err),
+                        });
+                        return;
+                    }
+                };
+                events.single_write(GameEvent::Notified {
+                    title: "Load".to_string(),
+                    body: "Picking up where you left off.".to_string(),
+                });
+                world.load(file.world);
+                *explored = file.explored;
+                *store = file.entities;
+                let player = file.player;
+                for (_, pos, region, character, inventory) in (
+                    &players,
+                    &mut positions,
+                    &mut regions,
+                    &mut characters,
+                    &mut inventories,
+                )
+                    .join()
+                {
+                    *pos = player.pos;
+                    *region = player.region;
+                    *character = player.character;
+                    *inventory = player.inventory.clone();
+                }
+                // everything else in the old regions goes, to be respawned along
+                // with the maps
+                for (entity, _) in (&entities, &regions).join() {
+                    if players.get(entity).is_none() {
+                        entities.delete(entity).expect("failed to delete entity");
+                    }
+                }
+                let ground = player.region.on_level(0);
+                *maps = RegionMaps::default();
+                maps.init(ground, CHUNK_RADIUS);
+                *collision_maps = CollisionMaps::default();
+                collision_maps.init(ground, CHUNK_RADIUS);
+                state.region = player.region;
+                state.stage = GameStage::Initializing;
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn save_file_round_trip() {
+        let mut world = WorldState::new(8);
+        world.quests.start("scrap_run");
+        world.set_flag("offered_help");
+        let file = SaveFile {
+            world,
+            player: SavedPlayer::default(),
+            explored: Explored::default(),
+            entities: EntityStore::default(),
+        };
+        let text = ron::ser::to_string(&file).unwrap();
+        let loaded: SaveFile = ron::de::from_str(&text).unwrap();
+        assert!(loaded.world.quests.get("scrap_run").is_some());
+        assert!(loaded.world.flags().contains("offered_help"));
+        assert_eq!(loaded.world.seed(), file.world.seed());
+    }
+}
@@ -28,6 +28,7 @@ impl<'a> System<'a> for Stage {
                     state.stage = GameStage::Initializing;
                 }
             }
+            GameStage::Dead => {}
         }
     }
 }
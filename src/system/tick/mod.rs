@@ -1,13 +1,27 @@
-use crate::component::Cursor;
+use crate::component::{Character, Cursor, Energy, MovementMode, Player, MOVE_COST};
 use crate::resource::UserInput;
-use crate::resource::{RegionMaps, GameState, UIQueue, WorldState};
-use specs::{Join, Read, ReadStorage, System, Write};
+use crate::resource::{
+    subscribe, GameEvent, GameEvents, GameStage, GameState, RegionMaps, UIQueue, WorldState,
+};
+use specs::{
+    Entities, Join, Read, ReadStorage, ReaderId, Resources, System, SystemData, Write, WriteStorage,
+};
 
-/// handles game state maintenance before a tick runs
+/// time passes in beats, this many to a standard action
+const BEATS_PER_ACTION: i32 = 4;
+/// how long a standard action takes, in hours
+const ACTION_HOURS: f32 = 1.0 / 60.0;
+
+/// handles game state maintenance before a tick runs. While the player is still
+/// recovering from their last action, time passes a beat per tick without waiting
+/// for input, and every actor gains energy at their own speed
 pub struct PreTick;
 impl<'a> System<'a> for PreTick {
     type SystemData = (
         ReadStorage<'a, Cursor>,
+        ReadStorage<'a, Player>,
+        ReadStorage<'a, Character>,
+        WriteStorage<'a, Energy>,
         Read<'a, UserInput>,
         Read<'a, UIQueue>,
         Read<'a, RegionMaps>,
@@ -15,7 +29,20 @@ impl<'a> System<'a> for PreTick {
         Write<'a, WorldState>,
     );
 
-    fn run(&mut self, (cursors, input, ui_queue, maps, mut state, mut world): Self::SystemData) {
+    fn run(
+        &mut self,
+        (
+            cursors,
+            players,
+            characters,
+            mut energies,
+            input,
+            ui_queue,
+            maps,
+            mut state,
+            mut world,
+        ): Self::SystemData,
+    ) {
         if ui_queue.len() > 0 {
             state.ticking = false;
             state.paused = true;
@@ -24,9 +51,16 @@ impl<'a> System<'a> for PreTick {
             state.ticking = true;
             state.paused = false;
         }
+        // nothing moves once the player is dead
+        if state.stage == GameStage::Dead {
+            state.ticking = false;
+            state.input_enabled = false;
+            return;
+        }
 
         let mut cursor_mode: bool = false;
         let mut has_input: bool = false;
+        let mut beat: bool = false;
         for _ in cursors.join() {
             cursor_mode = true; // if there's a cursor in play, we don't tick
         }
@@ -35,13 +69,21 @@ impl<'a> System<'a> for PreTick {
             has_input = true;
         }
 
+        let player_ready = match (&players, &energies).join().next() {
+            Some((_, energy)) => energy.ready(),
+            None => true,
+        };
+
         // FIXME this is getting junkier the more variables are in play
-        if !maps.populated() {
+        // only the player's own region has to be ready, the rest are built in the
+        // background
+        if !maps.ready(state.region) {
             state.ticking = false;
             state.input_enabled = false;
-        } else if state.fast_forward {
+        } else if state.fast_forward || !player_ready {
             state.ticking = true;
             state.input_enabled = false;
+            beat = true;
         } else {
             state.input_enabled = true;
             if has_input {
@@ -57,33 +99,61 @@ impl<'a> System<'a> for PreTick {
         state.frame += 1;
         if state.ticking {
             state.tick += 1;
-            world.time += (100.0 / 60.0) / 100.0;
-            if world.time >= 24.0 {
-                world.time = 0.0;
-                world.day += 1;
-            }
-            if world.day >= 365 {
-                if (world.year + 1) % 4 == 0 {
-                    // it was a leap year! but don't make the first year a leap year, that would be lame
-                    if world.day >= 366 {
-                        world.day = 0;
-                        world.year += 1;
-                    }
-                } else {
-                    world.day = 0;
-                    world.year += 1;
-                }
+        }
+        if beat {
+            let fraction = 1.0 / BEATS_PER_ACTION as f32;
+            for (energy, character) in (&mut energies, characters.maybe()).join() {
+                energy.gain(Energy::speed(character), fraction);
             }
+            world.advance_time(ACTION_HOURS * fraction);
         }
     }
 }
 
-/// Do any game state cleanup that should happen at the end of a turn
-pub struct PostTick;
+/// Do any game state cleanup that should happen at the end of a turn. Charges the
+/// player for whatever they did this turn
+#[derive(Default)]
+pub struct PostTick {
+    reader: Option<ReaderId<GameEvent>>,
+}
+
 impl<'a> System<'a> for PostTick {
-    type SystemData = (Write<'a, GameState>);
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, Player>,
+        ReadStorage<'a, MovementMode>,
+        WriteStorage<'a, Energy>,
+        Read<'a, GameEvents>,
+    );
 
-    fn run(&mut self, mut _state: Self::SystemData) {
-        /* TODO nothing so far */
+    fn setup(&mut self, res: &mut Resources) {
+        Self::SystemData::setup(res);
+        self.reader = Some(subscribe(res));
+    }
+
+    fn run(&mut self, (entities, players, modes, mut energies, events): Self::SystemData) {
+        let reader = match &mut self.reader {
+            Some(reader) => reader,
+            None => return,
+        };
+        let player = match (&entities, &players).join().next() {
+            Some((player, _)) => player,
+            None => return,
+        };
+        // each tile stepped costs what the player's pace makes it cost
+        let step_cost = modes.get(player).map_or(MOVE_COST, |mode| mode.step_cost());
+        let cost: i32 = events
+            .read(reader)
+            .map(|event| match event {
+                GameEvent::Moved { entity, .. } if *entity == player => step_cost,
+                GameEvent::RegionChanged => MOVE_COST,
+                GameEvent::Bumped { .. } => MOVE_COST,
+                GameEvent::Interacted(method, _) => method.cost(),
+                _ => 0,
+            })
+            .sum();
+        if let Some(energy) = energies.get_mut(player) {
+            energy.spend(cost);
+        }
     }
 }
@@ -0,0 +1,224 @@
+/// walks the player toward wherever they've asked to go, a step whenever they're
+/// ready, while time is fast-forwarded
+use crate::component::ai_brain::HOSTILE_STANDING;
+use crate::component::{Energy, Faction, MovePlan, Player, Pos, Region};
+use crate::resource::{
+    path_step, Assets, CollisionMaps, Explored, GameEvent, GameEvents, GameStage, GameState,
+    RegionMaps, Travel, TravelGoal, UIQueue, WorldState,
+};
+use specs::{Entities, Join, Read, ReadStorage, System, Write, WriteStorage};
+
+/// the regions bordering each other on the ground
+const SIDES: [(i32, i32); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// calls off travel, handing control back to the player and telling them why
+fn stop(travel: &mut Travel, state: &mut GameState, events: &mut GameEvents, why: &str) {
+    travel.stop();
+    state.fast_forward = false;
+    events.single_write(GameEvent::Notified {
+        title: "Travel".to_string(),
+        body: why.to_string(),
+    });
+}
+
+/// whether <pos> is on the edge of a region facing the neighbor <dx>, <dy> away
+fn on_edge(pos: Pos, dx: i32, dy: i32, width: usize, height: usize) -> bool {
+    (dx > 0 && pos.x == width - 1)
+        || (dx < 0 && pos.x == 0)
+        || (dy > 0 && pos.y == height - 1)
+        || (dy < 0 && pos.y == 0)
+}
+
+pub struct AutoTravel;
+impl<'a> System<'a> for AutoTravel {
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, Player>,
+        ReadStorage<'a, Pos>,
+        ReadStorage<'a, Region>,
+        ReadStorage<'a, Energy>,
+        ReadStorage<'a, Faction>,
+        WriteStorage<'a, MovePlan>,
+        Read<'a, Explored>,
+        Read<'a, RegionMaps>,
+        Read<'a, CollisionMaps>,
+        Read<'a, WorldState>,
+        Read<'a, Assets>,
+        Read<'a, UIQueue>,
+        Write<'a, Travel>,
+        Write<'a, GameState>,
+        Write<'a, GameEvents>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            players,
+            positions,
+            regions,
+            energies,
+            factions,
+            mut plans,
+            explored,
+            maps,
+            collisions,
+            world,
+            assets,
+            queue,
+            mut travel,
+            mut state,
+            mut events,
+        ): Self::SystemData,
+    ) {
+        let goal = match travel.goal {
+            Some(goal) => goal,
+            None => return,
+        };
+        if !state.fast_forward {
+            stop(&mut travel, &mut state, &mut events, "Stopped traveling.");
+            return;
+        }
+        if queue.len() > 0 {
+            stop(
+                &mut travel,
+                &mut state,
+                &mut events,
+                "Stopped traveling to look at something.",
+            );
+            return;
+        }
+        if state.stage != GameStage::Playing || !world.ready {
+            return;
+        }
+        let (player, pos, region) = match (&entities, &players, &positions, &regions).join().next()
+        {
+            Some((player, _, pos, region)) => (player, *pos, *region),
+            None => return,
+        };
+        // wait for the map to finish generating, and for the player to catch their breath
+        if !maps.ready(region) {
+            return;
+        }
+        if let Some(false) = energies.get(player).map(|e| e.ready()) {
+            return;
+        }
+        let hostile_in_view = (&factions, &positions, &regions).join().any(|(f, p, r)| {
+            explored.visible(world.wrap(*r), *p)
+                && world.get_reputation(&assets, &f.name) <= HOSTILE_STANDING
+        });
+        if hostile_in_view {
+            stop(
+                &mut travel,
+                &mut state,
+                &mut events,
+                "Something hostile is in view.",
+            );
+            return;
+        }
+
+        let map = maps.get(region);
+        let step = match goal {
+            TravelGoal::Explore => {
+                let (width, height) = (map.width(), map.height());
+                let known = world.wrap(region);
+                let unseen = path_step(map, &collisions, region, pos, |p| !explored.seen(known, p));
+                // canonical regions are all on the ground
+                if let (None, 0, Some(here)) = (unseen, region.z, world.canonical(region)) {
+                    travel.exhaust(here);
+                }
+                // once this region is seen, on into a neighbor not yet explored
+                let unexplored: Vec<(i32, i32)> = SIDES
+                    .iter()
+                    .cloned()
+                    .filter(|(dx, dy)| {
+                        world
+                            .canonical(Region::new(region.x + dx, region.y + dy))
+                            .map(|next| !travel.exhausted(next))
+                            == Some(true)
+                    })
+                    .collect();
+                let leaving = |p: Pos| {
+                    unexplored
+                        .iter()
+                        .any(|(dx, dy)| on_edge(p, *dx, *dy, width, height))
+                };
+                let crossing = unexplored
+                    .iter()
+                    .find(|(dx, dy)| on_edge(pos, *dx, *dy, width, height));
+                if unseen.is_some() {
+                    unseen
+                } else if region.z != 0 || unexplored.is_empty() {
+                    stop(
+                        &mut travel,
+                        &mut state,
+                        &mut events,
+                        "Nothing left to explore nearby.",
+                    );
+                    return;
+                } else if let Some((dx, dy)) = crossing {
+                    // step over the border and let the region system take it from there
+                    Some(MovePlan { x: *dx, y: *dy })
+                } else {
+                    path_step(map, &collisions, region, pos, leaving)
+                }
+            }
+            TravelGoal::To { pos: target, .. } => {
+                match travel.next_region(&world.road_graph, region) {
+                    Some(_) if region.z != 0 => {
+                        stop(
+                            &mut travel,
+                            &mut state,
+                            &mut events,
+                            "Can't travel on from in here.",
+                        );
+                        return;
+                    }
+                    Some(next) => {
+                        let (dx, dy) = (next.x - region.x, next.y - region.y);
+                        let (width, height) = (map.width(), map.height());
+                        if on_edge(pos, dx, dy, width, height) {
+                            // step over the border and let the region system take it from there
+                            Some(MovePlan { x: dx, y: dy })
+                        } else {
+                            path_step(map, &collisions, region, pos, |p| {
+                                on_edge(p, dx, dy, width, height)
+                            })
+                        }
+                    }
+                    None => {
+                        let target = match target {
+                            Some(target) => target,
+                            None => {
+                                stop(&mut travel, &mut state, &mut events, "Arrived.");
+                                return;
+                            }
+                        };
+                        // anything standing on the target only needs walking up to
+                        let blocked = collisions.get(region, target);
+                        let reached = |p: Pos| {
+                            p == target
+                                || (blocked
+                                    && (p.x as i32 - target.x as i32).abs() <= 1
+                                    && (p.y as i32 - target.y as i32).abs() <= 1)
+                        };
+                        if reached(pos) {
+                            stop(&mut travel, &mut state, &mut events, "Arrived.");
+                            return;
+                        }
+                        path_step(map, &collisions, region, pos, reached)
+                    }
+                }
+            }
+        };
+        match (step, plans.get_mut(player)) {
+            (Some(step), Some(plan)) => *plan = step,
+            _ => stop(
+                &mut travel,
+                &mut state,
+                &mut events,
+                "Can't find a way there.",
+            ),
+        }
+    }
+}
@@ -0,0 +1,115 @@
+/// passes actions and notifications between UI widgets and the game
+use crate::component::{Inventory, Player};
+use crate::resource::{
+    subscribe, Assets, DialogueEffect, GameEvent, GameEvents, GameState, Notification, Travel,
+    TravelGoal, UIAction, UIQueue, WorldState,
+};
+use specs::{
+    Join, Read, ReadStorage, ReaderId, Resources, System, SystemData, Write, WriteStorage,
+};
+
+/// applies a dialogue effect or quest reward, raising events for anything the
+/// player should hear about
+pub fn apply_effect(
+    effect: &DialogueEffect,
+    world: &mut WorldState,
+    assets: &Assets,
+    inventory: Option<&mut Inventory>,
+    events: &mut GameEvents,
+) {
+    match effect {
+        DialogueEffect::SetFlag(flag) => world.set_flag(flag),
+        DialogueEffect::GiveItem(item) => {
+            if let Some(inventory) = inventory {
+                inventory.add(item);
+                events.single_write(GameEvent::PickedUp);
+            }
+        }
+        DialogueEffect::ChangeStanding(faction, change) => {
+            world.change_reputation(assets, faction, *change);
+        }
+        DialogueEffect::StartQuest(quest) => match assets.get_quest(quest) {
+            Some(template) if world.quests.start(quest) => {
+                events.single_write(GameEvent::Notified {
+                    title: format!("New quest: {}", template.title),
+                    body: template.description.clone(),
+                });
+            }
+            Some(_) => {}
+            None => println!("quest {} does not exist", quest),
+        },
+    }
+}
+
+#[derive(Default)]
+pub struct UIActions;
+
+impl<'a> System<'a> for UIActions {
+    type SystemData = (
+        ReadStorage<'a, Player>,
+        WriteStorage<'a, Inventory>,
+        Write<'a, UIQueue>,
+        Write<'a, WorldState>,
+        Read<'a, Assets>,
+        Write<'a, GameEvents>,
+        Write<'a, Travel>,
+        Write<'a, GameState>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            players,
+            mut inventories,
+            mut queue,
+            mut world,
+            assets,
+            mut events,
+            mut travel,
+            mut state,
+        ): Self::SystemData,
+    ) {
+        for action in queue.take_actions() {
+            match action {
+                UIAction::Dialogue(effect) => {
+                    let inventory = (&players, &mut inventories).join().next().map(|(_, i)| i);
+                    apply_effect(&effect, &mut world, &assets, inventory, &mut events);
+                }
+                UIAction::Interact(method, pos) => {
+                    events.single_write(GameEvent::Interacted(method, pos));
+                }
+                UIAction::Travel(region) => {
+                    travel.start(TravelGoal::To { region, pos: None });
+                    state.fast_forward = true;
+                }
+            }
+        }
+    }
+}
+
+/// turns notification events into notification widgets
+#[derive(Default)]
+pub struct ShowNotifications {
+    reader: Option<ReaderId<GameEvent>>,
+}
+
+impl<'a> System<'a> for ShowNotifications {
+    type SystemData = (Read<'a, GameEvents>, Write<'a, UIQueue>);
+
+    fn setup(&mut self, res: &mut Resources) {
+        Self::SystemData::setup(res);
+        self.reader = Some(subscribe(res));
+    }
+
+    fn run(&mut self, (events, mut queue): Self::SystemData) {
+        let reader = match &mut self.reader {
+            Some(reader) => reader,
+            None => return,
+        };
+        for event in events.read(reader) {
+            if let GameEvent::Notified { title, body } = event {
+                queue.add(Notification::new(title.clone(), body.clone()));
+            }
+        }
+    }
+}
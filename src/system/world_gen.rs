@@ -1,11 +1,45 @@
+use rand::seq::{IteratorRandom, SliceRandom};
+use rand::{Rng as _, SeedableRng};
+use rand_pcg::Pcg32;
 use specs::{Read, System, Write};
 use tcod::noise::*;
 use tcod::random::{Algo, Rng};
 
-use crate::component::Region;
+use crate::component::{Direction, Region};
 use crate::constants::*;
-use crate::resource::{Assets, GameStage, GameState, WorldState};
+use crate::resource::{
+    road_name, Assets, GameStage, GameState, Hydrology, PointOfInterest, Residents, RoadCurve,
+    RoadGraph, Settlement, WorldState,
+};
 use crate::util::rand_up;
+use std::collections::HashSet;
+
+/// regions lower than this are under the sea
+const SEA_LEVEL: f32 = 0.3;
+/// rivers only start in regions at least this high
+const SPRING_LEVEL: f32 = 0.6;
+/// roughly one river is attempted per this many regions
+const RIVER_RARITY: usize = 1500;
+/// rivers longer than this are cut off (avoids wandering forever on flat ground)
+const MAX_RIVER_LENGTH: usize = 400;
+/// how much colder the highest ground is than the lowest
+const ELEVATION_CHILL: f32 = 0.5;
+/// extra moisture for regions along rivers and coasts
+const WATERSIDE_MOISTURE: f32 = 0.25;
+/// road networks covering fewer regions than this are removed
+const MIN_ROAD_NETWORK: usize = 6;
+/// regions at least this crowded can be the center of a town or city
+const CENTER_POP: f32 = 0.8;
+/// population centers closer together than this count as one
+const CENTER_SPACING: i32 = 12;
+/// highways only join population centers at most this far apart
+const MAX_HIGHWAY_LENGTH: i32 = 60;
+/// how many other centers each population center is joined to
+const HIGHWAYS_PER_CENTER: usize = 2;
+/// how far a highway bows away from a straight line, relative to its length
+const HIGHWAY_BEND: f32 = 0.3;
+/// points of interest are at least this many regions apart
+const POI_SPACING: i32 = 3;
 
 pub struct WorldGen;
 
@@ -14,7 +48,12 @@ impl<'a> System<'a> for WorldGen {
 
     fn run(&mut self, (state, mut world, assets): Self::SystemData) {
         if !world.ready && state.stage == GameStage::Initializing {
-            println!("generating new world with seed {}", world.seed());
+            println!(
+                "generating new world of size {} with seed {}",
+                world.size(),
+                world.seed()
+            );
+            world.reset_layers();
             let rng = Rng::new_with_seed(Algo::CMWC, world.seed());
             let noise = Noise::init_with_dimensions(2)
                 .noise_type(NoiseType::Simplex)
@@ -24,7 +63,26 @@ impl<'a> System<'a> for WorldGen {
             for x in world.min_x()..world.max_x() {
                 for y in world.min_y()..world.max_y() {
                     let region = Region::new(x, y);
-                    let pop = rand_up(noise.get_fbm([x as f32 * 0.0125, y as f32 * 0.0125], 32));
+                    // elevation is sampled well away from the population field so
+                    // the two don't line up
+                    let height = rand_up(
+                        noise.get_fbm([x as f32 * 0.02 + 1000.0, y as f32 * 0.02 + 1000.0], 16),
+                    );
+                    world.set_height(region, height);
+                }
+            }
+            generate_hydrology(&mut world);
+            generate_climate(&mut world, &noise);
+
+            for x in world.min_x()..world.max_x() {
+                for y in world.min_y()..world.max_y() {
+                    let region = Region::new(x, y);
+                    let mut pop =
+                        rand_up(noise.get_fbm([x as f32 * 0.0125, y as f32 * 0.0125], 32));
+                    if world.get_water(region).kind == Hydrology::Sea {
+                        // nobody lives at sea
+                        pop = 0.0;
+                    }
 
                     // generate pre-apocalypse population density
                     world.set_pop(region, pop);
@@ -38,17 +96,144 @@ impl<'a> System<'a> for WorldGen {
                     world.choose_geography(&noise, &assets, region);
                 }
             }
-            clean_roads(&mut world);
             extend_roads_x(&mut world);
             extend_roads_y(&mut world);
             // run extend x again to give it something to connect to
             extend_roads_x(&mut world);
+            clean_roads(&mut world);
+            generate_highways(&mut world);
+            world.road_graph = RoadGraph::build(&world);
+            println!(
+                "road graph: {} intersections, {} roads",
+                world.road_graph.nodes().len(),
+                world.road_graph.edges().len()
+            );
+            generate_points_of_interest(&mut world, &assets);
+            // a loaded game already knows how its settlements are doing
+            if world.sim.settlements.is_empty() {
+                settle_points_of_interest(&mut world, &assets);
+            }
             world.ready = true;
             println!("finished world generation");
         }
     }
 }
 
+/// marks seas by elevation, runs rivers downhill from high ground until they
+/// reach other water (or pool into lakes), then marks the coasts
+fn generate_hydrology(world: &mut WorldState) {
+    for x in world.min_x()..world.max_x() {
+        for y in world.min_y()..world.max_y() {
+            let region = Region::new(x, y);
+            if world.get_height(region) < SEA_LEVEL {
+                world.set_water(region, Hydrology::Sea, None);
+            } else {
+                world.set_water(region, Hydrology::Dry, None);
+            }
+        }
+    }
+
+    let rng = &mut Pcg32::seed_from_u64(u64::from(world.seed()));
+    let size = (world.max_x() - world.min_x()) as usize;
+    let mut rivers = 0;
+    for _ in 0..(size * size / RIVER_RARITY) {
+        let spring = Region::new(
+            rng.gen_range(world.min_x(), world.max_x()),
+            rng.gen_range(world.min_y(), world.max_y()),
+        );
+        if world.get_height(spring) >= SPRING_LEVEL
+            && world.get_water(spring).kind == Hydrology::Dry
+        {
+            run_river(world, spring);
+            rivers += 1;
+        }
+    }
+
+    let mut coasts = 0;
+    for x in world.min_x()..world.max_x() {
+        for y in world.min_y()..world.max_y() {
+            let region = Region::new(x, y);
+            if world.get_water(region).kind != Hydrology::Dry {
+                continue;
+            }
+            let shore = neighbors(region)
+                .iter()
+                .any(|(_, n)| world.contains(*n) && world.get_water(*n).kind.is_body());
+            if shore {
+                world.set_water(region, Hydrology::Coast, None);
+                coasts += 1;
+            }
+        }
+    }
+    println!(
+        "hydrology pass: {} rivers, {} coastal regions",
+        rivers, coasts
+    );
+}
+
+/// sets moisture and temperature for every region. Temperature rises from the
+/// cold north to the warm south and falls with elevation, while moisture is
+/// highest near water
+fn generate_climate(world: &mut WorldState, noise: &Noise) {
+    let span = (world.max_y() - world.min_y()) as f32;
+    for x in world.min_x()..world.max_x() {
+        for y in world.min_y()..world.max_y() {
+            let region = Region::new(x, y);
+            let latitude = (y - world.min_y()) as f32 / span;
+            let variation = noise.get_fbm([x as f32 * 0.03 + 3000.0, y as f32 * 0.03], 8) * 0.2;
+            let chill = (world.get_height(region) - SEA_LEVEL).max(0.0) * ELEVATION_CHILL;
+            let temperature = 0.2 + latitude * 0.6 + variation - chill;
+            world.set_temperature(region, temperature.clamp(0.0, 1.0));
+
+            let base = rand_up(noise.get_fbm([x as f32 * 0.015 + 2000.0, y as f32 * 0.015], 16));
+            let moisture = match world.get_water(region).kind {
+                Hydrology::Dry => base,
+                Hydrology::Coast | Hydrology::River => base + WATERSIDE_MOISTURE,
+                Hydrology::Lake | Hydrology::Sea => 1.0,
+            };
+            world.set_moisture(region, moisture.clamp(0.0, 1.0));
+        }
+    }
+}
+
+/// the four regions bordering <region>, with the direction to each
+fn neighbors(region: Region) -> [(Direction, Region); 4] {
+    [
+        (Direction::North, Region::new(region.x, region.y - 1)),
+        (Direction::South, Region::new(region.x, region.y + 1)),
+        (Direction::East, Region::new(region.x + 1, region.y)),
+        (Direction::West, Region::new(region.x - 1, region.y)),
+    ]
+}
+
+/// follows the steepest way down from <spring>, marking the river as it goes. A
+/// river that reaches a low point with nowhere lower to go pools into a lake
+fn run_river(world: &mut WorldState, spring: Region) {
+    let mut current = spring;
+    for _ in 0..MAX_RIVER_LENGTH {
+        let height = world.get_height(current);
+        let lowest = neighbors(current)
+            .iter()
+            .filter(|(_, n)| world.contains(*n))
+            .map(|(dir, n)| (*dir, *n, world.get_height(*n)))
+            .min_by(|a, b| a.2.partial_cmp(&b.2).unwrap_or(std::cmp::Ordering::Equal));
+        match lowest {
+            Some((dir, next, next_height)) if next_height < height => {
+                world.set_water(current, Hydrology::River, Some(dir));
+                if world.get_water(next).kind != Hydrology::Dry {
+                    // joined the sea, a lake or another river
+                    return;
+                }
+                current = next;
+            }
+            _ => {
+                world.set_water(current, Hydrology::Lake, None);
+                return;
+            }
+        }
+    }
+}
+
 fn road_lanes(noise: &Noise, sample_coord: [f32; 2], pop: f32) -> u8 {
     let base_sample = rand_up(noise.get_fbm(sample_coord, 8));
     let mut adj_sample: f32;
@@ -78,23 +263,203 @@ fn road_lanes(noise: &Noise, sample_coord: [f32; 2], pop: f32) -> u8 {
     adj_sample.min(7.0).max(0.0).round() as u8
 }
 
-/// cleans up orphaned roads
+/// removes fragments of road too small to connect to anything worthwhile
 fn clean_roads(world: &mut WorldState) {
-    let mut orphans: u32 = 0;
-    // sweep through and remove all orphans
-    for y in world.min_y() + 1..world.max_y() - 1 {
-        for x in world.min_x() + 1..world.max_x() - 1 {
-            let left = world.get_road(Region::new(x - 1, y)).lanes_x;
-            let right = world.get_road(Region::new(x + 1, y)).lanes_x;
-            let above = world.get_road(Region::new(x, y - 1)).lanes_y;
-            let below = world.get_road(Region::new(x, y + 1)).lanes_y;
-            if left + right + above + below == 0 {
-                world.set_road(Region::new(x, y), 0, 0);
-                orphans += 1;
+    let fragments = RoadGraph::build(world).fragments(MIN_ROAD_NETWORK);
+    for region in fragments.iter() {
+        world.set_road(*region, 0, 0);
+    }
+    println!("road orphan pass: removed {} orphans", fragments.len());
+}
+
+/// finds the middle of every town and city: the most crowded region around,
+/// with no busier center nearby
+fn population_centers(world: &WorldState) -> Vec<Region> {
+    let mut candidates: Vec<(Region, f32)> = Vec::new();
+    for x in world.min_x()..world.max_x() {
+        for y in world.min_y()..world.max_y() {
+            let region = Region::new(x, y);
+            let pop = world.get_pop(region);
+            if pop < CENTER_POP {
+                continue;
+            }
+            let peak = (-1..=1)
+                .flat_map(|dx| (-1..=1).map(move |dy| Region::new(x + dx, y + dy)))
+                .all(|other| world.get_pop(other) <= pop);
+            if peak {
+                candidates.push((region, pop));
+            }
+        }
+    }
+    candidates.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    let mut centers: Vec<Region> = Vec::new();
+    for (region, _) in candidates {
+        if centers
+            .iter()
+            .all(|c| distance(*c, region) >= CENTER_SPACING)
+        {
+            centers.push(region);
+        }
+    }
+    centers
+}
+
+fn distance(a: Region, b: Region) -> i32 {
+    (((a.x - b.x).pow(2) + (a.y - b.y).pow(2)) as f32).sqrt() as i32
+}
+
+/// joins nearby population centers with sweeping highways, which run across
+/// the road grid instead of along it
+fn generate_highways(world: &mut WorldState) {
+    let centers = population_centers(world);
+    let rng = &mut Pcg32::seed_from_u64(u64::from(world.seed()).wrapping_add(1));
+    let mut used: HashSet<String> = HashSet::new();
+    let mut joined: HashSet<(usize, usize)> = HashSet::new();
+    for (i, center) in centers.iter().enumerate() {
+        let mut nearest: Vec<(usize, i32)> = centers
+            .iter()
+            .enumerate()
+            .filter(|(j, _)| *j != i)
+            .map(|(j, other)| (j, distance(*center, *other)))
+            .filter(|(_, dist)| *dist <= MAX_HIGHWAY_LENGTH)
+            .collect();
+        nearest.sort_by_key(|(_, dist)| *dist);
+        for (j, _) in nearest.into_iter().take(HIGHWAYS_PER_CENTER) {
+            if !joined.insert((i.min(j), i.max(j))) {
+                continue;
+            }
+            let curve = highway(world, rng, *center, centers[j]);
+            // highways don't cross seas or lakes
+            let wet = curve
+                .regions()
+                .iter()
+                .any(|region| world.get_water(*region).kind.is_body());
+            if wet {
+                continue;
+            }
+            let name = road_name(rng, &used, curve.axis(), curve.lanes);
+            used.insert(name.clone());
+            world.add_road_curve(RoadCurve { name, ..curve });
+        }
+    }
+    println!(
+        "highway pass: joined {} population centers with {} highways",
+        centers.len(),
+        world.road_curves.len()
+    );
+}
+
+/// a curving highway from the middle of region <a> to the middle of region <b>
+fn highway(world: &WorldState, rng: &mut Pcg32, a: Region, b: Region) -> RoadCurve {
+    let start = [a.x as f32 + 0.5, a.y as f32 + 0.5];
+    let end = [b.x as f32 + 0.5, b.y as f32 + 0.5];
+    // bend to one side of the straight line between the two
+    let bend = rng.gen_range(-HIGHWAY_BEND, HIGHWAY_BEND);
+    let control = [
+        (start[0] + end[0]) / 2.0 - (end[1] - start[1]) * bend,
+        (start[1] + end[1]) / 2.0 + (end[0] - start[0]) * bend,
+    ];
+    let quieter = world.get_pop(a).min(world.get_pop(b));
+    RoadCurve {
+        points: [start, control, end],
+        lanes: if quieter > 0.9 { 6 } else { 4 },
+        name: String::new(),
+    }
+}
+
+/// scatters towns, camps and other points of interest around the world, each
+/// in a region matching its template
+fn generate_points_of_interest(world: &mut WorldState, assets: &Assets) {
+    let rng = &mut Pcg32::seed_from_u64(u64::from(world.seed()).wrapping_add(2));
+    // sorted so the same seed always gives the same places
+    let mut templates: Vec<_> = assets.get_points_of_interest().iter().collect();
+    templates.sort_by(|a, b| a.0.cmp(b.0));
+    let mut counts = vec![0; templates.len()];
+    let mut used: HashSet<&str> = HashSet::new();
+    let mut regions: Vec<Region> = (world.min_x()..world.max_x())
+        .flat_map(|x| (world.min_y()..world.max_y()).map(move |y| Region::new(x, y)))
+        .collect();
+    regions.shuffle(rng);
+    for region in regions {
+        if world.get_water(region).kind.is_body() {
+            continue;
+        }
+        let crowded = (-POI_SPACING + 1..POI_SPACING).any(|dx| {
+            (-POI_SPACING + 1..POI_SPACING).any(|dy| {
+                let other = Region::new(region.x + dx, region.y + dy);
+                world.get_point_of_interest(other).is_some()
+            })
+        });
+        if crowded {
+            continue;
+        }
+        let pop = world.get_pop(region);
+        for (i, (template_name, template)) in templates.iter().enumerate() {
+            if template.max_count.unwrap_or(usize::MAX) <= counts[i]
+                || !template.fits_population(pop)
+                || rng.gen_range(0.0, 1.0) >= template.frequency
+            {
+                continue;
+            }
+            let name = match template
+                .names
+                .iter()
+                .filter(|name| !used.contains(name.as_str()))
+                .choose(rng)
+            {
+                Some(name) => {
+                    used.insert(name);
+                    name.clone()
+                }
+                None => template.kind.clone(),
+            };
+            world.add_point_of_interest(PointOfInterest {
+                template: template_name.to_string(),
+                name,
+                region,
+            });
+            counts[i] += 1;
+            break;
+        }
+    }
+    println!(
+        "points of interest pass: placed {} points of interest",
+        world.points_of_interest.len()
+    );
+}
+
+/// starts the world simulation off with a settlement at every point of interest,
+/// rolling its residents from the point of interest's template
+fn settle_points_of_interest(world: &mut WorldState, assets: &Assets) {
+    let rng = &mut Pcg32::seed_from_u64(u64::from(world.seed()).wrapping_add(3));
+    let mut settlements = Vec::new();
+    for poi in &world.points_of_interest {
+        let (region, template) = match (
+            world.canonical(poi.region),
+            assets.get_point_of_interest(&poi.template),
+        ) {
+            (Some(region), Some(template)) => (region, template),
+            _ => continue,
+        };
+        let mut settlement = Settlement::new(
+            region,
+            world.get_moisture(region),
+            rng.gen_range(0.2, 0.8),
+        );
+        // only those with a mind of their own come and go
+        for entity in &template.entities {
+            if assets.get_entity(&entity.template).map(|e| e.has_brain()) == Some(true) {
+                settlement.residents.push(Residents {
+                    template: entity.template.clone(),
+                    count: u32::from(rng.gen_range(entity.min, entity.max + 1)),
+                    capacity: u32::from(entity.max) * 2,
+                });
             }
         }
+        settlements.push(settlement);
     }
-    println!("road orphan pass: removed {} orphans", orphans);
+    println!("settled {} points of interest", settlements.len());
+    world.sim.settlements = settlements;
 }
 
 /// sweep all the horizontal roads, connecting and smoothing out lane transitions
@@ -0,0 +1,104 @@
+/// runs the coarse world simulation, and keeps it in step with what's going on
+/// in the regions that are loaded
+use crate::component::{Pos, Region, Template};
+use crate::resource::{Assets, CollisionMaps, GameStage, GameState, RegionMaps, WorldState};
+use rand::prelude::*;
+use rand_pcg::Pcg32;
+use specs::{Builder, Entities, Join, LazyUpdate, Read, ReadStorage, System, Write};
+use std::collections::HashMap;
+
+/// spots tried when looking for somewhere to put a migrant
+const MAX_TRIES: u8 = 30;
+
+pub struct SimulateWorld;
+impl<'a> System<'a> for SimulateWorld {
+    type SystemData = (
+        Entities<'a>,
+        Read<'a, LazyUpdate>,
+        ReadStorage<'a, Template>,
+        ReadStorage<'a, Region>,
+        Read<'a, RegionMaps>,
+        Read<'a, CollisionMaps>,
+        Read<'a, Assets>,
+        Read<'a, GameState>,
+        Write<'a, WorldState>,
+    );
+
+    fn run(
+        &mut self,
+        (entities, lazy, templates, regions, maps, collisions, assets, state, mut world): Self::SystemData,
+    ) {
+        if state.stage != GameStage::Playing || !world.ready {
+            return;
+        }
+        // loaded ground regions, by where they are in the world simulation
+        let loaded: HashMap<Region, Region> = maps
+            .regions()
+            .filter(|region| region.z == 0 && maps.ready(*region))
+            .filter_map(|region| world.canonical(region).map(|c| (c, region)))
+            .collect();
+
+        // settlements in loaded regions count whoever is actually there
+        let mut census: HashMap<(Region, &str), u32> = HashMap::new();
+        for (template, region) in (&templates, &regions).join() {
+            if let Some(region) = world.canonical(region.on_level(0)) {
+                *census.entry((region, &template.name)).or_insert(0) += 1;
+            }
+        }
+        for settlement in world.sim.settlements.iter_mut() {
+            settlement.loaded = loaded.contains_key(&settlement.region);
+            if !settlement.loaded {
+                continue;
+            }
+            for residents in settlement.residents.iter_mut() {
+                let key = (settlement.region, residents.template.as_str());
+                residents.count = census.get(&key).cloned().unwrap_or(0);
+            }
+        }
+
+        // the simulation is rolled from the seed, so a world plays out the same
+        // each time it's generated
+        let day = world.day;
+        let rng = &mut Pcg32::seed_from_u64(u64::from(world.seed()) << 32 | u64::from(day));
+
+        // migrants reaching a loaded settlement turn up in person. Those only
+        // passing through stay on the road
+        let sim = &mut world.sim;
+        sim.migrations.retain(|migration| {
+            if migration.region != migration.to {
+                return true;
+            }
+            let region = match loaded.get(&migration.region) {
+                Some(region) => *region,
+                None => return true,
+            };
+            let map = maps.get(region);
+            let spot = (0..MAX_TRIES)
+                .map(|_| {
+                    Pos::new(
+                        rng.gen_range(0, map.width()),
+                        rng.gen_range(0, map.height()),
+                    )
+                })
+                .find(|pos| {
+                    map.get(*pos).map(|t| t.walkable) == Some(true) && !collisions.get(region, *pos)
+                });
+            match (spot, assets.get_entity(&migration.template)) {
+                (Some(pos), Some(template)) => {
+                    template
+                        .to_lazy(&lazy, &entities)
+                        .with(pos)
+                        .with(region)
+                        .with(Template::new(&migration.template))
+                        .build();
+                    false
+                }
+                // nowhere to stand, so they'll try again later
+                (None, Some(_)) => true,
+                (_, None) => false,
+            }
+        });
+
+        world.sim.advance(day, rng);
+    }
+}